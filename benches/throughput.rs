@@ -0,0 +1,91 @@
+//! Compare reading an `Encoder` one frame at a time with coalesced reads.
+//!
+//! The queue is filled before the timer starts, so only the read side is
+//! measured. Every read is written to a file, standing in for the write to a
+//! socket that each read costs a server.
+//!
+//! Run with `cargo bench --bench throughput`.
+
+use async_sse::{Encoder, EncoderBuilder};
+use async_std::task;
+use futures_lite::prelude::*;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const EVENTS: usize = 200_000;
+const BUF_SIZE: usize = 8 * 1024;
+
+/// Create an encoder with every event already queued.
+async fn filled_encoder() -> Encoder {
+    let (sender, encoder) = EncoderBuilder::new().capacity(EVENTS).build();
+    for n in 0..EVENTS {
+        sender.send("tick", &n.to_string(), None).await.unwrap();
+    }
+    encoder
+}
+
+/// The file every read is written to.
+fn output() -> PathBuf {
+    std::env::temp_dir().join("async-sse-throughput")
+}
+
+/// Read one queued frame per call, like the encoder did before coalescing.
+async fn read_per_frame(mut encoder: Encoder, out: &mut File) -> (usize, usize) {
+    let (mut bytes, mut reads) = (0, 0);
+    loop {
+        let frame = encoder.fill_buf().await.unwrap();
+        let n = frame.len();
+        if n == 0 {
+            return (bytes, reads);
+        }
+        out.write_all(frame).unwrap();
+        encoder.consume(n);
+        bytes += n;
+        reads += 1;
+    }
+}
+
+/// Read as many queued frames per call as fit the buffer.
+async fn read_coalesced(mut encoder: Encoder, out: &mut File) -> (usize, usize) {
+    let (mut bytes, mut reads) = (0, 0);
+    let mut buf = vec![0; BUF_SIZE];
+    loop {
+        let n = encoder.read(&mut buf).await.unwrap();
+        if n == 0 {
+            return (bytes, reads);
+        }
+        out.write_all(&buf[..n]).unwrap();
+        bytes += n;
+        reads += 1;
+    }
+}
+
+fn report(name: &str, (bytes, reads): (usize, usize), elapsed: Duration) {
+    let mib = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:>10}: {:>8} reads, {:>7.1} bytes/read, {:>8.1} MiB/s",
+        name,
+        reads,
+        bytes as f64 / reads as f64,
+        mib / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    task::block_on(async {
+        let mut out = File::create(output()).unwrap();
+        let encoder = filled_encoder().await;
+        let start = Instant::now();
+        let res = read_per_frame(encoder, &mut out).await;
+        report("per frame", res, start.elapsed());
+
+        let mut out = File::create(output()).unwrap();
+        let encoder = filled_encoder().await;
+        let start = Instant::now();
+        let res = read_coalesced(encoder, &mut out).await;
+        report("coalesced", res, start.elapsed());
+    });
+    let _ = std::fs::remove_file(output());
+}
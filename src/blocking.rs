@@ -0,0 +1,236 @@
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::lines::{BlockingLines, LineBuf};
+use crate::{wire, DecoderBuilder, DecoderState, Event, Message, Parser, RetryUnit};
+
+/// Decode an SSE connection from a blocking reader.
+///
+/// This is the blocking counterpart of [`decode`], and parses the stream the
+/// same way. See [`DecoderBuilder::build_blocking`] to configure the decoder.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{decode_blocking, Event};
+///
+/// let mut decoder = decode_blocking(&b"event:cat\ndata:chashu\n\n"[..]);
+/// match decoder.next().unwrap()? {
+///     Event::Message(msg) => assert_eq!(msg.data(), b"chashu"),
+///     Event::Retry(_) => unreachable!(),
+/// }
+/// assert!(decoder.next().is_none());
+/// # http_types::Result::Ok(())
+/// ```
+///
+/// [`decode`]: crate::decode
+pub fn decode_blocking<R: BufRead>(reader: R) -> BlockingDecoder<R> {
+    DecoderBuilder::new().build_blocking(reader)
+}
+
+/// A blocking SSE protocol decoder.
+///
+/// Iterates over the events read from a [`BufRead`].
+#[derive(Debug)]
+pub struct BlockingDecoder<R> {
+    /// The lines decoder.
+    lines: BlockingLines<R>,
+    /// The parser state.
+    parser: Parser,
+}
+
+impl<R: BufRead> BlockingDecoder<R> {
+    pub(crate) fn new(reader: R, buf: LineBuf, parser: Parser) -> Self {
+        Self {
+            lines: BlockingLines::new(reader, buf),
+            parser,
+        }
+    }
+
+    /// The id of the last event dispatched, to send as `Last-Event-ID` when
+    /// reconnecting.
+    ///
+    /// See [`Decoder::last_event_id`](crate::Decoder::last_event_id).
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.last_event_id()
+    }
+
+    /// The reconnection time most recently set by the server, if any.
+    pub fn reconnection_time(&self) -> Option<Duration> {
+        self.parser.reconnection_time()
+    }
+
+    /// A copy of the state to carry over to the next connection.
+    pub fn state(&self) -> DecoderState {
+        self.parser.state()
+    }
+
+    /// Move the state out, to carry over to the next connection.
+    ///
+    /// Pass it to [`DecoderBuilder::state`] to resume from it.
+    pub fn into_state(self) -> DecoderState {
+        self.parser.into_state()
+    }
+
+    /// Access the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.lines.reader
+    }
+
+    /// Mutably access the underlying reader.
+    ///
+    /// Reading from it directly can corrupt the stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.lines.reader
+    }
+
+    /// Take back the underlying reader.
+    ///
+    /// A partially read line is discarded.
+    pub fn into_inner(self) -> R {
+        self.lines.reader
+    }
+}
+
+impl<R: BufRead> Iterator for BlockingDecoder<R> {
+    type Item = http_types::Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in &mut self.lines {
+            match self.parser.next_line(line) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        None
+    }
+}
+
+/// A blocking SSE writer which encodes events straight into a [`Write`].
+///
+/// This is the blocking counterpart of [`SseWriter`], and produces the same
+/// bytes.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::BlockingWriter;
+///
+/// let mut writer = BlockingWriter::new(Vec::new());
+/// writer.send("cat", "chashu", None)?;
+/// assert_eq!(writer.get_ref(), b"event:cat\ndata:chashu\n\n");
+/// # std::io::Result::Ok(())
+/// ```
+///
+/// [`SseWriter`]: crate::SseWriter
+#[derive(Debug)]
+pub struct BlockingWriter<W> {
+    writer: W,
+    buf: Vec<u8>,
+    flush_per_event: bool,
+    retry_unit: RetryUnit,
+}
+
+impl<W: Write> BlockingWriter<W> {
+    /// Create a new writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: Vec::new(),
+            flush_per_event: true,
+            retry_unit: RetryUnit::default(),
+        }
+    }
+
+    /// Set whether the underlying writer is flushed after every event.
+    ///
+    /// Defaults to `true`. Disable this to batch events, and call
+    /// [`BlockingWriter::flush`] instead.
+    pub fn flush_per_event(mut self, flush: bool) -> Self {
+        self.flush_per_event = flush;
+        self
+    }
+
+    /// Set the unit `retry` fields are sent in.
+    ///
+    /// Defaults to [`RetryUnit::Milliseconds`], as the spec requires.
+    pub fn retry_unit(mut self, unit: RetryUnit) -> Self {
+        self.retry_unit = unit;
+        self
+    }
+
+    /// Send a new message.
+    ///
+    /// See [`Sender::send`] for how the fields are encoded and validated.
+    ///
+    /// [`Sender::send`]: crate::Sender::send
+    pub fn send<'a>(
+        &mut self,
+        name: impl Into<Option<&'a str>>,
+        data: &str,
+        id: Option<&str>,
+    ) -> io::Result<()> {
+        wire::write_message(&mut self.buf, name.into(), data.as_bytes(), id)?;
+        self.write_frame()
+    }
+
+    /// Send a [`Message`].
+    pub fn send_message(&mut self, msg: &Message) -> io::Result<()> {
+        wire::encode_message(msg, &mut self.buf)?;
+        self.write_frame()
+    }
+
+    /// Send an [`Event`].
+    pub fn send_event(&mut self, event: &Event) -> io::Result<()> {
+        match event {
+            Event::Message(msg) => self.send_message(msg),
+            Event::Retry(dur) => self.send_retry(*dur, None),
+        }
+    }
+
+    /// Send a new "retry" message.
+    pub fn send_retry(&mut self, dur: Duration, id: Option<&str>) -> io::Result<()> {
+        wire::write_retry(&mut self.buf, dur, id, self.retry_unit)?;
+        self.write_frame()
+    }
+
+    /// Send a comment.
+    pub fn send_comment(&mut self, text: &str) -> io::Result<()> {
+        wire::encode_comment(text, &mut self.buf);
+        self.write_frame()
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Access the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Mutably access the underlying writer.
+    ///
+    /// Writing to it directly can corrupt the stream.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Take back the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write the frame in `buf`, and flush if configured to.
+    fn write_frame(&mut self) -> io::Result<()> {
+        let res = self.writer.write_all(&self.buf);
+        self.buf.clear();
+        res?;
+        if self.flush_per_event {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A future returned by [`Clock::sleep`].
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>;
+
+/// A source of timers.
+///
+/// The encoder uses a clock to schedule keep-alive comments. Implement this
+/// trait to drive timers from a runtime of your choice, or to control time
+/// from tests.
+pub trait Clock: Debug + Send + Sync + 'static {
+    /// Create a future which resolves once `dur` has elapsed.
+    fn sleep(&self, dur: Duration) -> Sleep;
+}
+
+/// A clock backed by the system's monotonic time.
+///
+/// Timers are driven by [`async-io`](https://docs.rs/async-io), which does not
+/// depend on any particular async runtime. Requires the `async-io` feature,
+/// which is enabled by default.
+#[cfg(feature = "async-io")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "async-io")]
+impl Clock for SystemClock {
+    fn sleep(&self, dur: Duration) -> Sleep {
+        Box::pin(async move {
+            async_io::Timer::after(dur).await;
+        })
+    }
+}
@@ -1,24 +1,233 @@
-use crate::Lines;
+use crate::lines::LineBuf;
+use crate::{Lines, Parser};
 use futures_lite::prelude::*;
 use futures_lite::ready;
 use std::task::{Context, Poll};
 
+use std::io::BufRead;
 use std::pin::Pin;
+use std::time::Duration;
 
-use crate::Event;
+use crate::{BlockingDecoder, Event, RetryUnit};
 
 /// Decode a new incoming SSE connection.
+///
+/// See [`DecoderBuilder`] to configure the decoder.
 pub fn decode<R>(reader: R) -> Decoder<R>
 where
     R: AsyncBufRead + Unpin,
 {
-    Decoder {
-        lines: Lines::new(reader),
-        processed_bom: false,
-        buffer: vec![],
-        last_event_id: None,
-        event_type: None,
-        data: vec![],
+    DecoderBuilder::new().build(reader)
+}
+
+/// Decode a new incoming SSE connection, resuming from an earlier one.
+///
+/// Messages without an `id` field report the id carried over in `state`, so
+/// ids stay continuous across reconnects. See [`Decoder::into_state`].
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{decode, decode_with_state, Event};
+/// use async_std::io::Cursor;
+/// use async_std::prelude::*;
+///
+/// # async_std::task::block_on(async {
+/// let mut first = decode(Cursor::new("id:42\ndata:chashu\n\n"));
+/// while let Some(event) = first.next().await {
+///     event?;
+/// }
+///
+/// // Reconnect, sending `Last-Event-ID: 42`.
+/// let state = first.into_state();
+/// assert_eq!(state.last_event_id(), Some("42"));
+/// let mut second = decode_with_state(Cursor::new("data:nori\n\n"), state);
+/// match second.next().await.unwrap()? {
+///     Event::Message(msg) => assert_eq!(msg.id().as_deref(), Some("42")),
+///     Event::Retry(_) => unreachable!(),
+/// }
+/// # http_types::Result::Ok(())
+/// # });
+/// ```
+pub fn decode_with_state<R>(reader: R, state: DecoderState) -> Decoder<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    DecoderBuilder::new().state(state).build(reader)
+}
+
+/// The state a decoder carries over from one connection to the next.
+///
+/// This holds the last event id, and the reconnection time set by the server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecoderState {
+    last_event_id: Option<String>,
+    reconnection_time: Option<Duration>,
+}
+
+impl DecoderState {
+    /// Create a new, empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the last event id, such as the `Last-Event-ID` a client sent when
+    /// reconnecting.
+    ///
+    /// An empty id means there is no last event id, like an empty `id` field.
+    pub fn with_last_event_id(mut self, id: impl Into<String>) -> Self {
+        self.last_event_id = Some(id.into()).filter(|id| !id.is_empty());
+        self
+    }
+
+    /// Set the reconnection time.
+    pub fn with_reconnection_time(mut self, dur: Duration) -> Self {
+        self.reconnection_time = Some(dur);
+        self
+    }
+
+    /// The last event id.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// The reconnection time.
+    pub fn reconnection_time(&self) -> Option<Duration> {
+        self.reconnection_time
+    }
+
+    pub(crate) fn into_parts(self) -> (Option<String>, Option<Duration>) {
+        (self.last_event_id, self.reconnection_time)
+    }
+
+    pub(crate) fn from_parts(
+        last_event_id: Option<String>,
+        reconnection_time: Option<Duration>,
+    ) -> Self {
+        Self {
+            last_event_id,
+            reconnection_time,
+        }
+    }
+}
+
+/// Configure and create an SSE decoder.
+#[derive(Debug, Clone, Default)]
+pub struct DecoderBuilder {
+    retry_unit: RetryUnit,
+    strict_utf8: bool,
+    max_line_len: Option<usize>,
+    max_event_size: Option<usize>,
+    max_id_len: Option<usize>,
+    skip_oversized: bool,
+    state: DecoderState,
+}
+
+impl DecoderBuilder {
+    /// Create a new builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the unit `retry` fields are read in.
+    ///
+    /// Defaults to [`RetryUnit::Milliseconds`], as the spec requires.
+    pub fn retry_unit(mut self, unit: RetryUnit) -> Self {
+        self.retry_unit = unit;
+        self
+    }
+
+    /// Set whether invalid UTF-8 fails the stream.
+    ///
+    /// By default, invalid UTF-8 sequences are replaced with U+FFFD
+    /// REPLACEMENT CHARACTER and decoding continues, as the spec requires.
+    /// In strict mode, a line containing invalid UTF-8 is instead returned as
+    /// an error of kind [`InvalidData`](std::io::ErrorKind::InvalidData).
+    pub fn strict_utf8(mut self, strict: bool) -> Self {
+        self.strict_utf8 = strict;
+        self
+    }
+
+    /// Set the maximum length of a line, in bytes, not counting its line
+    /// ending.
+    ///
+    /// The decoder stops buffering a line as soon as it exceeds the limit, so
+    /// this bounds memory use even if a line never ends. Unlimited by default.
+    /// See [`DecoderBuilder::skip_oversized`] for what happens to the event.
+    pub fn max_line_len(mut self, max: usize) -> Self {
+        self.max_line_len = Some(max);
+        self
+    }
+
+    /// Set the maximum size of an event's data, in bytes.
+    ///
+    /// This counts the line feed between `data` lines. Unlimited by default.
+    pub fn max_event_size(mut self, max: usize) -> Self {
+        self.max_event_size = Some(max);
+        self
+    }
+
+    /// Set the maximum length of an event id, in bytes.
+    ///
+    /// Unlimited by default. An id which is too long is ignored, along with
+    /// the event it belongs to.
+    pub fn max_id_len(mut self, max: usize) -> Self {
+        self.max_id_len = Some(max);
+        self
+    }
+
+    /// Set whether events which exceed a limit are skipped silently.
+    ///
+    /// By default, the decoder returns a [`LimitExceeded`](crate::LimitExceeded) error wrapped in an
+    /// [`io::Error`](std::io::Error), then skips the event and keeps going.
+    /// In lenient mode, the event is skipped without an error.
+    ///
+    /// Either way, every field from the one which exceeded the limit up to the
+    /// end of the event is ignored, including `id` and `retry` fields.
+    pub fn skip_oversized(mut self, skip: bool) -> Self {
+        self.skip_oversized = skip;
+        self
+    }
+
+    /// Set the state to start from, such as the state of the decoder for a
+    /// previous connection.
+    ///
+    /// See [`decode_with_state`].
+    pub fn state(mut self, state: DecoderState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Create a blocking decoder reading from `reader`.
+    pub fn build_blocking<R: BufRead>(self, reader: R) -> BlockingDecoder<R> {
+        let buf = self.line_buf();
+        BlockingDecoder::new(reader, buf, self.parser())
+    }
+
+    /// Create a decoder reading from `reader`.
+    pub fn build<R>(self, reader: R) -> Decoder<R>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let buf = self.line_buf();
+        Decoder {
+            lines: Lines::new(reader, buf),
+            parser: self.parser(),
+        }
+    }
+
+    fn line_buf(&self) -> LineBuf {
+        LineBuf::new(self.strict_utf8, self.max_line_len)
+    }
+
+    fn parser(self) -> Parser {
+        Parser::new(
+            self.retry_unit,
+            self.max_event_size,
+            self.max_id_len,
+            self.skip_oversized,
+            self.state,
+        )
     }
 }
 
@@ -27,138 +236,74 @@ where
 pub struct Decoder<R: AsyncBufRead + Unpin> {
     /// The lines decoder.
     lines: Lines<R>,
-    /// Have we processed the optional Byte Order Marker on the first line?
-    processed_bom: bool,
-    /// Was the last character of the previous line a \r?
-    /// Bytes that were fed to the decoder but do not yet form a message.
-    buffer: Vec<u8>,
-    /// The _last event ID_ buffer.
-    last_event_id: Option<String>,
-    /// The _event type_ buffer.
-    event_type: Option<String>,
-    /// The _data_ buffer.
-    data: Vec<u8>,
+    /// The parser state.
+    parser: Parser,
 }
 
 impl<R: AsyncBufRead + Unpin> Decoder<R> {
-    fn take_message(&mut self) -> Option<Event> {
-        if self.data.is_empty() {
-            // If the data buffer is an empty string, set the data buffer and
-            // the event type buffer to the empty string [and return.]
-            self.event_type.take();
-            None
-        } else {
-            // Removing tailing newlines
-            if self.data.ends_with(&[b'\n']) {
-                self.data.pop();
-            }
-            let name = self.event_type.take().unwrap_or("message".to_string());
-            let data = std::mem::replace(&mut self.data, vec![]);
-            // The _last event ID_ buffer persists between messages.
-            let id = self.last_event_id.clone();
-            Some(Event::new_msg(name, data, id))
-        }
+    /// The id of the last event dispatched, to send as `Last-Event-ID` when
+    /// reconnecting.
+    ///
+    /// This is updated at the end of every frame, even one without data, and
+    /// stays available after the stream ended or returned an error.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.last_event_id()
+    }
+
+    /// The reconnection time most recently set by the server, if any.
+    ///
+    /// The same duration is also reported once as an [`Event::Retry`].
+    pub fn reconnection_time(&self) -> Option<Duration> {
+        self.parser.reconnection_time()
+    }
+
+    /// A copy of the state to carry over to the next connection.
+    pub fn state(&self) -> DecoderState {
+        self.parser.state()
+    }
+
+    /// Move the state out, to carry over to the next connection.
+    ///
+    /// See [`decode_with_state`].
+    pub fn into_state(self) -> DecoderState {
+        self.parser.into_state()
+    }
+
+    /// Access the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.lines.reader
+    }
+
+    /// Mutably access the underlying reader.
+    ///
+    /// Reading from it directly can corrupt the stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.lines.reader
+    }
+
+    /// Take back the underlying reader.
+    ///
+    /// A partially read line is discarded.
+    pub fn into_inner(self) -> R {
+        self.lines.reader
     }
 }
 
 impl<R: AsyncBufRead + Unpin> Stream for Decoder<R> {
     type Item = http_types::Result<Event>;
 
-    // This function uses two loops: one to get lines from the reader.
-    // And one to parse each line delimited by `:`.
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         loop {
             // Get the next line, if available.
             let line = match ready!(Pin::new(&mut self.lines).poll_next(cx)) {
                 None => return Poll::Ready(None),
-                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
-                Some(Ok(line)) => line,
+                Some(line) => line,
             };
-
-            // Get rid of the BOM at the start
-            let line = if !self.processed_bom && line.starts_with("\u{feff}") {
-                self.processed_bom = true;
-                &line[3..]
-            } else {
-                &line
-            };
-
-            log::trace!("> new line: {:?}", line);
-            let mut parts = line.splitn(2, ':');
-            loop {
-                match (parts.next(), parts.next()) {
-                    // If the field name is "retry":
-                    (Some("retry"), Some(value)) if value.chars().all(|c| c.is_ascii_digit()) => {
-                        log::trace!("> retry");
-                        // If the field value consists of only ASCII digits, then interpret the field value
-                        // as an integer in base ten, and set the event stream's reconnection time to that
-                        // integer. Otherwise, ignore the field.
-                        if let Ok(time) = value.parse::<u64>() {
-                            return Poll::Ready(Some(Ok(Event::new_retry(time))));
-                        }
-                    }
-                    // If the field name is "event":
-                    (Some("event"), Some(value)) => {
-                        log::trace!("> event");
-                        // Set the event type buffer to field value.
-                        self.event_type = Some(strip_leading_space(value).to_string());
-                    }
-                    // If the field name is "data":
-                    (Some("data"), value) => {
-                        log::trace!("> data: {:?}", &value);
-                        // Append the field value to the data buffer,
-                        if let Some(value) = value {
-                            self.data.extend(strip_leading_space_b(value.as_bytes()));
-                            // then append a single U+000A LINE FEED (LF) character to the data buffer.
-                        }
-                        self.data.push(b'\n');
-                    }
-                    // If the field name is "id":
-                    (Some("id"), Some(id_str)) if !id_str.contains(char::from(0)) => {
-                        log::trace!("> id");
-                        // If the field value does not contain U+0000 NULL, then set the last event ID buffer to the field value.
-                        // Otherwise, ignore the field.
-                        self.last_event_id = Some(strip_leading_space(id_str).to_string());
-                        // return Poll::Ready(Ok(self.take_message()).transpose());
-                    }
-                    // Comment
-                    (Some(""), Some(_)) => (log::trace!("> comment")),
-                    // End of frame
-                    (Some(""), None) => {
-                        log::trace!("> end of frame");
-                        match self.take_message() {
-                            Some(event) => {
-                                log::trace!("> end of frame [event]: {:?}", event);
-                                return Poll::Ready(Some(Ok(event)));
-                            }
-                            None => {
-                                log::trace!("> end of frame, break");
-                                break;
-                            }
-                        };
-                    }
-                    (_, _) => {
-                        break;
-                    }
-                };
+            match self.parser.next_line(line) {
+                Ok(Some(event)) => return Poll::Ready(Some(Ok(event))),
+                Ok(None) => continue,
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
             }
         }
     }
 }
-
-/// Remove a leading space (code point 0x20) from a string slice.
-fn strip_leading_space(input: &str) -> &str {
-    if input.starts_with(' ') {
-        &input[1..]
-    } else {
-        input
-    }
-}
-
-fn strip_leading_space_b(input: &[u8]) -> &[u8] {
-    if input.starts_with(&[b' ']) {
-        &input[1..]
-    } else {
-        input
-    }
-}
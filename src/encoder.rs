@@ -2,71 +2,179 @@ use futures_lite::prelude::*;
 use futures_lite::ready;
 use std::task::{Context, Poll};
 
-use std::io;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, IoSliceMut};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::clock::{Clock, Sleep};
+use crate::sink::SenderSink;
+use crate::{wire, Event, InvalidField, Message, RetryUnit};
+
+/// A frame queued for the encoder.
+///
+/// With the `bytes` feature, frames are reference counted so a single frame
+/// can be queued for many encoders without being copied.
+#[cfg(feature = "bytes")]
+pub(crate) type Chunk = bytes::Bytes;
+#[cfg(not(feature = "bytes"))]
+pub(crate) type Chunk = Vec<u8>;
+
+/// Turn an encoded frame into a `Chunk`, without copying it.
+#[cfg(feature = "bytes")]
+fn chunk(buf: Vec<u8>) -> Chunk {
+    Chunk::from(buf)
+}
+#[cfg(not(feature = "bytes"))]
+fn chunk(buf: Vec<u8>) -> Chunk {
+    buf
+}
+
+/// The comment line sent when the stream has been idle for too long.
+const KEEP_ALIVE: &[u8] = b":\n";
+
 pin_project_lite::pin_project! {
     /// An SSE protocol encoder.
     #[derive(Debug)]
     pub struct Encoder {
-        buf: Box<[u8]>,
+        buf: Chunk,
         cursor: usize,
-        #[pin]
-        receiver: async_channel::Receiver<Vec<u8>>,
+        // The receiver isn't `Unpin`, but the encoder needs to be.
+        receiver: Pin<Box<async_channel::Receiver<Chunk>>>,
+        keep_alive: Option<KeepAlive>,
+        // Declared after `receiver` so the channel is closed by the time
+        // senders are notified.
+        closed: NotifyOnDrop,
     }
 }
 
-impl AsyncRead for Encoder {
-    fn poll_read(
-        self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &mut [u8],
-    ) -> Poll<io::Result<usize>> {
-        let mut this = self.project();
+/// Wake up every `Sender::closed` future when the encoder is dropped.
+#[derive(Debug)]
+struct NotifyOnDrop(Arc<Shared>);
+
+impl Drop for NotifyOnDrop {
+    fn drop(&mut self) {
+        self.0.closed.notify(usize::MAX);
+    }
+}
+
+impl Encoder {
+    /// Make sure the current buffer has bytes left to read.
+    ///
+    /// Returns `false` once every `Sender` has been dropped and the queue is
+    /// empty.
+    fn poll_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<bool> {
+        let this = self.project();
         // Request a new buffer if current one is exhausted.
         if this.buf.len() <= *this.cursor {
-            match ready!(this.receiver.as_mut().poll_next(cx)) {
-                Some(buf) => {
+            match this.receiver.as_mut().poll_next(cx) {
+                Poll::Ready(Some(buf)) => {
                     log::trace!("> Received a new buffer with len {}", buf.len());
-                    *this.buf = buf.into_boxed_slice();
+                    *this.buf = buf;
                     *this.cursor = 0;
+                    if let Some(keep_alive) = this.keep_alive {
+                        keep_alive.reset();
+                    }
                 }
-                None => {
+                Poll::Ready(None) => {
                     log::trace!("> Encoder done reading");
-                    return Poll::Ready(Ok(0));
+                    return Poll::Ready(false);
                 }
+                Poll::Pending => match this.keep_alive {
+                    Some(keep_alive) => {
+                        ready!(keep_alive.poll_elapsed(cx));
+                        log::trace!("> Sending keep-alive comment");
+                        *this.buf = KEEP_ALIVE.into();
+                        *this.cursor = 0;
+                    }
+                    None => return Poll::Pending,
+                },
             };
         }
+        Poll::Ready(true)
+    }
+}
 
-        // Write the current buffer to completion.
-        let local_buf = &this.buf[*this.cursor..];
-        let max = buf.len().min(local_buf.len());
-        buf[..max].clone_from_slice(&local_buf[..max]);
-        *this.cursor += max;
+impl Encoder {
+    /// Copy as many queued bytes into `out` as fit, without waiting.
+    ///
+    /// Frames which are already queued are read in the same call, so a busy
+    /// stream needs fewer, larger reads.
+    fn read_queued(self: Pin<&mut Self>, out: &mut [u8]) -> usize {
+        let this = self.project();
+        let mut read = 0;
+        loop {
+            // Write the current buffer to completion.
+            let local_buf = &this.buf[*this.cursor..];
+            let max = (out.len() - read).min(local_buf.len());
+            out[read..read + max].clone_from_slice(&local_buf[..max]);
+            *this.cursor += max;
+            read += max;
+            if read == out.len() {
+                return read;
+            }
+
+            // Move on to the next buffer, if one is ready.
+            match this.receiver.try_recv() {
+                Ok(buf) => {
+                    log::trace!("> Coalescing a new buffer with len {}", buf.len());
+                    *this.buf = buf;
+                    *this.cursor = 0;
+                    if let Some(keep_alive) = this.keep_alive.as_mut() {
+                        keep_alive.reset();
+                    }
+                }
+                Err(_) => return read,
+            }
+        }
+    }
+}
+
+impl AsyncRead for Encoder {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if !ready!(self.as_mut().poll_buf(cx)) {
+            return Poll::Ready(Ok(0));
+        }
 
         // Return bytes read.
-        Poll::Ready(Ok(max))
+        Poll::Ready(Ok(self.read_queued(buf)))
+    }
+
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        if !ready!(self.as_mut().poll_buf(cx)) {
+            return Poll::Ready(Ok(0));
+        }
+
+        let mut read = 0;
+        for buf in bufs {
+            let n = self.as_mut().read_queued(buf);
+            read += n;
+            // The queue is empty.
+            if n < buf.len() {
+                break;
+            }
+        }
+        Poll::Ready(Ok(read))
     }
 }
 
 impl AsyncBufRead for Encoder {
-    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
-        let mut this = self.project();
-        // Request a new buffer if current one is exhausted.
-        if this.buf.len() <= *this.cursor {
-            match ready!(this.receiver.as_mut().poll_next(cx)) {
-                Some(buf) => {
-                    log::trace!("> Received a new buffer with len {}", buf.len());
-                    *this.buf = buf.into_boxed_slice();
-                    *this.cursor = 0;
-                }
-                None => {
-                    log::trace!("> Encoder done reading");
-                    return Poll::Ready(Ok(&[]));
-                }
-            };
+    fn poll_fill_buf(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        if !ready!(self.as_mut().poll_buf(cx)) {
+            return Poll::Ready(Ok(&[]));
         }
+        let this = self.project();
         Poll::Ready(Ok(&this.buf[*this.cursor..]))
     }
 
@@ -76,67 +184,596 @@ impl AsyncBufRead for Encoder {
     }
 }
 
+/// The keep-alive timer of an `Encoder`.
+struct KeepAlive {
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    sleep: Sleep,
+}
+
+impl KeepAlive {
+    fn new(interval: Duration, clock: Arc<dyn Clock>) -> Self {
+        let sleep = clock.sleep(interval);
+        Self {
+            interval,
+            clock,
+            sleep,
+        }
+    }
+
+    /// Restart the timer, because something was sent.
+    fn reset(&mut self) {
+        self.sleep = self.clock.sleep(self.interval);
+    }
+
+    /// Wait for the interval to elapse, and restart the timer.
+    fn poll_elapsed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        ready!(self.sleep.as_mut().poll(cx));
+        self.reset();
+        Poll::Ready(())
+    }
+}
+
+impl fmt::Debug for KeepAlive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeepAlive")
+            .field("interval", &self.interval)
+            .field("clock", &self.clock)
+            .finish()
+    }
+}
+
+/// Configure and create an SSE encoder.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::EncoderBuilder;
+/// use std::time::Duration;
+///
+/// # #[cfg(feature = "async-io")] {
+/// let (sender, encoder) = EncoderBuilder::new()
+///     .keep_alive(Duration::from_secs(15))
+///     .build();
+/// # drop((sender, encoder));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EncoderBuilder {
+    keep_alive: Option<Duration>,
+    clock: Option<Arc<dyn Clock>>,
+    retry_unit: RetryUnit,
+    capacity: usize,
+    backpressure: Backpressure,
+}
+
+impl EncoderBuilder {
+    /// Create a new builder with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            keep_alive: None,
+            clock: default_clock(),
+            retry_unit: RetryUnit::default(),
+            capacity: 1,
+            backpressure: Backpressure::default(),
+        }
+    }
+
+    /// Send a `:` comment line whenever no event has been sent for `interval`.
+    ///
+    /// This keeps proxies and load balancers from closing idle connections.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Set the clock used to schedule keep-alive comments and send timeouts.
+    ///
+    /// Defaults to `SystemClock` with the `async-io` feature. Without it,
+    /// there is no default clock, and one has to be set to use keep-alive
+    /// comments or [`Sender::send_timeout`].
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Set the unit `retry` fields are sent in.
+    ///
+    /// Defaults to [`RetryUnit::Milliseconds`], as the spec requires.
+    pub fn retry_unit(mut self, unit: RetryUnit) -> Self {
+        self.retry_unit = unit;
+        self
+    }
+
+    /// Set how many events can be queued before the encoder reads them.
+    ///
+    /// Defaults to 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity cannot be zero");
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set what happens when an event is sent while the queue is full.
+    ///
+    /// Defaults to [`Backpressure::Block`].
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Create the encoder + sender pair.
+    ///
+    /// # Panics
+    ///
+    /// Panics if keep-alive comments are enabled without a clock, which can
+    /// only happen without the `async-io` feature.
+    pub fn build(self) -> (Sender, Encoder) {
+        let (sender, receiver) = async_channel::bounded(self.capacity);
+        let keep_alive = self.keep_alive.map(|interval| {
+            let clock = self.clock.clone().expect("keep-alive requires a clock");
+            KeepAlive::new(interval, clock)
+        });
+        let shared = Arc::new(Shared {
+            retry_unit: self.retry_unit,
+            clock: self.clock,
+            backpressure: self.backpressure,
+            dropped: AtomicU64::new(0),
+            closed: event_listener::Event::new(),
+        });
+        let encoder = Encoder {
+            receiver: Box::pin(receiver),
+            buf: Chunk::default(),
+            cursor: 0,
+            keep_alive,
+            closed: NotifyOnDrop(shared.clone()),
+        };
+        let sender = Sender { sender, shared };
+        (sender, encoder)
+    }
+}
+
+impl Default for EncoderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The clock an `EncoderBuilder` starts with.
+#[cfg(feature = "async-io")]
+fn default_clock() -> Option<Arc<dyn Clock>> {
+    Some(Arc::new(crate::SystemClock))
+}
+#[cfg(not(feature = "async-io"))]
+fn default_clock() -> Option<Arc<dyn Clock>> {
+    None
+}
+
 /// The sending side of the encoder.
+///
+/// # Cancellation
+///
+/// Every event is queued as a single frame. If a future returned by one of the
+/// `send` methods is dropped before it completes, nothing of that event has
+/// been written to the `Encoder`: an event is either queued in full, or not at
+/// all.
 #[derive(Debug, Clone)]
-pub struct Sender(async_channel::Sender<Vec<u8>>);
+pub struct Sender {
+    sender: async_channel::Sender<Chunk>,
+    shared: Arc<Shared>,
+}
+
+/// State shared between all clones of a `Sender`.
+#[derive(Debug)]
+struct Shared {
+    retry_unit: RetryUnit,
+    clock: Option<Arc<dyn Clock>>,
+    backpressure: Backpressure,
+    /// The number of events dropped because the queue was full.
+    dropped: AtomicU64,
+    /// Notified when the channel is closed.
+    closed: event_listener::Event,
+}
+
+/// What a [`Sender`] does when an event is sent while the queue is full.
+///
+/// Every policy but `Block` completes sends without waiting, so one slow
+/// client can't hold up the code producing events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backpressure {
+    /// Wait until there is room in the queue.
+    #[default]
+    Block,
+    /// Drop the oldest queued event to make room for the new one.
+    DropOldest,
+    /// Drop the new event.
+    DropNewest,
+    /// Fail the send with an error of kind `Other`, wrapping a
+    /// [`TrySendError::Full`] which can be told apart from other errors with
+    /// [`io::Error::get_ref`]. The kind isn't `WouldBlock`, which I/O
+    /// adapters take as a signal to retry.
+    Fail,
+    /// Drop the new event and close the stream. The encoder reads the events
+    /// which are still queued, then ends; every later send fails.
+    Disconnect,
+}
+
+/// The error returned by [`Sender::try_send`].
+///
+/// Every variant hands back the event which couldn't be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The queue is full.
+    Full(T),
+    /// The encoder has gone away.
+    Disconnected(T),
+    /// The event has an invalid name or id.
+    Invalid(T, InvalidField),
+}
+
+impl<T> TrySendError<T> {
+    /// Take back the event which couldn't be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(t) | Self::Disconnected(t) | Self::Invalid(t, _) => t,
+        }
+    }
+
+    /// Check whether the queue was full.
+    pub fn is_full(&self) -> bool {
+        matches!(self, Self::Full(_))
+    }
+
+    /// Check whether the encoder has gone away.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, Self::Disconnected(_))
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("sse queue is full"),
+            Self::Disconnected(_) => f.write_str("sse disconnected"),
+            Self::Invalid(_, err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for TrySendError<T> {}
 
 /// Create a new SSE encoder.
+///
+/// See [`EncoderBuilder`] to configure the encoder.
 pub fn encode() -> (Sender, Encoder) {
-    let (sender, receiver) = async_channel::bounded(1);
-    let encoder = Encoder {
-        receiver,
-        buf: Box::default(),
-        cursor: 0,
-    };
-    (Sender(sender), encoder)
+    EncoderBuilder::new().build()
 }
 
 impl Sender {
-    async fn inner_send(&self, bytes: impl Into<Vec<u8>>) -> io::Result<()> {
-        self.0
-            .send(bytes.into())
+    pub(crate) async fn inner_send(&self, bytes: impl Into<Chunk>) -> io::Result<()> {
+        let bytes: Chunk = bytes.into();
+        match self.shared.backpressure {
+            Backpressure::Block => self.sender.send(bytes).await.map_err(|_| disconnected()),
+            _ => match self.try_enqueue(bytes) {
+                Ok(()) => Ok(()),
+                Err(async_channel::TrySendError::Full(_)) => {
+                    Err(io::Error::other(TrySendError::Full(())))
+                }
+                Err(async_channel::TrySendError::Closed(_)) => Err(disconnected()),
+            },
+        }
+    }
+
+    /// Queue a frame without waiting, applying the backpressure policy if the
+    /// queue is full.
+    ///
+    /// A full queue is only reported with `Block` and `Fail`.
+    fn try_enqueue(&self, bytes: Chunk) -> Result<(), async_channel::TrySendError<Chunk>> {
+        match self.shared.backpressure {
+            Backpressure::DropOldest => match self.sender.force_send(bytes) {
+                Ok(displaced) => {
+                    if displaced.is_some() {
+                        log::trace!("> Queue full, dropped the oldest event");
+                        self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Ok(())
+                }
+                Err(err) => Err(async_channel::TrySendError::Closed(err.into_inner())),
+            },
+            policy => match self.sender.try_send(bytes) {
+                Err(async_channel::TrySendError::Full(bytes)) => self.queue_full(policy, bytes),
+                res => res,
+            },
+        }
+    }
+
+    /// Apply the backpressure policy to a frame which didn't fit the queue.
+    fn queue_full(
+        &self,
+        policy: Backpressure,
+        bytes: Chunk,
+    ) -> Result<(), async_channel::TrySendError<Chunk>> {
+        log::trace!("> Queue full, applying {:?}", policy);
+        if matches!(policy, Backpressure::Block | Backpressure::Fail) {
+            return Err(async_channel::TrySendError::Full(bytes));
+        }
+
+        self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+        if policy == Backpressure::Disconnect {
+            self.close_channel();
+            return Err(async_channel::TrySendError::Closed(bytes));
+        }
+        Ok(())
+    }
+
+    /// Close the channel, and wake up every `closed` future.
+    fn close_channel(&self) -> bool {
+        let closed = self.sender.close();
+        self.shared.closed.notify(usize::MAX);
+        closed
+    }
+
+    /// Close the stream.
+    ///
+    /// Events which are already queued are still read by the [`Encoder`],
+    /// which then reports the end of the stream, even if clones of this
+    /// sender are still alive. Every later send from any clone fails.
+    ///
+    /// Returns `true` if this call closed the stream, and `false` if it was
+    /// already closed.
+    pub fn close(&self) -> bool {
+        log::trace!("> Closing the stream");
+        self.close_channel()
+    }
+
+    /// Send a final retry hint and a comment, then close the stream.
+    ///
+    /// This tells clients how long to wait before reconnecting, for example
+    /// when a server is shutting down. The final frame waits for room in the
+    /// queue, whatever the [`Backpressure`] policy, and is read after every
+    /// event queued before it. See [`Sender::close`] for what happens next.
+    ///
+    /// The stream is closed even if the final frame can't be sent, or the
+    /// returned future is dropped before it completes. In that case, the final
+    /// frame isn't sent at all.
+    pub async fn close_with_retry(&self, retry: Duration, comment: &str) -> io::Result<()> {
+        let _close = CloseOnDrop(self);
+        let mut msg = Vec::new();
+        wire::encode_comment(comment, &mut msg);
+        wire::write_retry(&mut msg, retry, None, self.shared.retry_unit)?;
+        self.sender
+            .send(chunk(msg))
             .await
-            .map_err(|_| io::Error::new(io::ErrorKind::ConnectionAborted, "sse disconnected"))
+            .map_err(|_| disconnected())
+    }
+
+    /// Turn this sender into a [`Sink`](futures_sink::Sink) of events.
+    pub fn into_sink(self) -> SenderSink {
+        SenderSink::new(self)
+    }
+
+    /// Check whether the stream has been closed.
+    ///
+    /// This is the case once the [`Encoder`] has been dropped, for example
+    /// because the client went away, once [`Sender::close`] has been called,
+    /// or once the stream was disconnected by the [`Backpressure`] policy.
+    /// Every send fails from then on.
+    pub fn is_closed(&self) -> bool {
+        // The channel closes itself once its receiver count drops to zero.
+        self.sender.is_closed()
+    }
+
+    /// Wait until the stream has been closed.
+    ///
+    /// This resolves as soon as the [`Encoder`] is dropped, without waiting
+    /// for the next send to fail, so resources backing the stream can be
+    /// released right away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # futures_lite::future::block_on(async {
+    /// let (sender, encoder) = async_sse::encode();
+    /// drop(encoder);
+    /// sender.closed().await;
+    /// assert!(sender.is_closed());
+    /// # });
+    /// ```
+    pub async fn closed(&self) {
+        loop {
+            if self.is_closed() {
+                return;
+            }
+            let listener = self.shared.closed.listen();
+            if self.is_closed() {
+                return;
+            }
+            listener.await;
+        }
+    }
+
+    /// The number of events dropped so far because the queue was full.
+    ///
+    /// The count is shared between all clones of this sender. Events are only
+    /// ever dropped with [`Backpressure::DropOldest`],
+    /// [`Backpressure::DropNewest`], and [`Backpressure::Disconnect`].
+    pub fn dropped_events(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
     }
 
     /// Send a new message over SSE.
+    ///
+    /// The whole event is written into a single buffer before it is queued,
+    /// so events sent concurrently from cloned senders never interleave.
+    /// Dropping the returned future before it completes discards the event.
+    ///
+    /// The data is sent as one `data` line per line of text, so decoding it
+    /// yields exactly the same string. The only exception are line endings:
+    /// clients report CR and CRLF as a single LF, as the spec requires.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` wrapping an [`InvalidField`]
+    /// if the name or id contains a line break, or the id contains U+0000
+    /// NULL. Nothing is sent in that case. Use [`EventName::escape`] and
+    /// [`EventId::escape`] to send such values anyway.
+    ///
+    /// [`InvalidField`]: crate::InvalidField
+    /// [`EventName::escape`]: crate::EventName::escape
+    /// [`EventId::escape`]: crate::EventId::escape
     pub async fn send(
         &self,
         name: impl Into<Option<&str>>,
         data: &str,
         id: Option<&str>,
     ) -> io::Result<()> {
-        // Write the event name
-        if let Some(name) = name.into() {
-            self.inner_send(format!("event:{}\n", name)).await?;
-        }
+        let mut msg = Vec::new();
+        wire::write_message(&mut msg, name.into(), data.as_bytes(), id)?;
+        self.inner_send(msg).await
+    }
 
-        // Write the id
-        if let Some(id) = id {
-            self.inner_send(format!("id:{}\n", id)).await?;
-        }
+    /// Send a [`Message`] over SSE.
+    ///
+    /// This is useful to relay messages produced by a [`Decoder`]. The data is
+    /// sent as is, even if it isn't valid UTF-8. The event name is omitted if
+    /// it's the default "message".
+    ///
+    /// Like [`Sender::send`], the event is either queued in full or not at
+    /// all, and an invalid name or id is rejected.
+    ///
+    /// [`Decoder`]: crate::Decoder
+    pub async fn send_message(&self, msg: &Message) -> io::Result<()> {
+        let mut buf = Vec::new();
+        wire::encode_message(msg, &mut buf)?;
+        self.inner_send(buf).await
+    }
+
+    /// Send an [`Event`] over SSE.
+    ///
+    /// Messages are sent like [`Sender::send_message`], and retries like
+    /// [`Sender::send_retry`].
+    pub async fn send_event(&self, event: &Event) -> io::Result<()> {
+        let buf = self.encode_event(event)?;
+        self.inner_send(buf).await
+    }
+
+    /// Send an encoded [`Frame`] over SSE.
+    ///
+    /// The frame's bytes are shared rather than copied, so the same frame can
+    /// be sent to many encoders cheaply.
+    ///
+    /// Like [`Sender::send`], the frame is either queued in full or not at all.
+    ///
+    /// [`Frame`]: crate::Frame
+    #[cfg(feature = "bytes")]
+    pub async fn send_frame(&self, frame: &crate::Frame) -> io::Result<()> {
+        self.inner_send(frame.clone().into_bytes()).await
+    }
 
-        // Write the data section, and end.
-        for line in data.lines() {
-            let msg = format!("data:{}\n", line);
-            self.inner_send(msg).await?;
+    /// Try to send an [`Event`] without waiting.
+    ///
+    /// The event is either queued in full, or not at all. A full queue is
+    /// handled by the encoder's [`Backpressure`] policy, like with
+    /// [`Sender::send`], except that `Block` can't wait: like `Fail`, it
+    /// reports [`TrySendError::Full`]. `Disconnect` reports
+    /// [`TrySendError::Disconnected`]. Every error hands the event back.
+    pub fn try_send(&self, event: Event) -> Result<(), TrySendError<Event>> {
+        let buf = match self.encode_event(&event) {
+            Ok(buf) => buf,
+            Err(err) => return Err(TrySendError::Invalid(event, err)),
+        };
+        match self.try_enqueue(chunk(buf)) {
+            Ok(()) => Ok(()),
+            Err(async_channel::TrySendError::Full(_)) => Err(TrySendError::Full(event)),
+            Err(async_channel::TrySendError::Closed(_)) => Err(TrySendError::Disconnected(event)),
         }
-        self.inner_send("\n").await?;
+    }
 
-        Ok(())
+    /// Send an [`Event`], giving up after `timeout`.
+    ///
+    /// The timeout is driven by the encoder's [`Clock`], so it doesn't depend
+    /// on any particular async runtime. If it elapses, the event is not sent at
+    /// all and an error of kind `TimedOut` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `Unsupported` if the encoder has no clock,
+    /// which can only happen without the `async-io` feature.
+    pub async fn send_timeout(&self, event: &Event, timeout: Duration) -> io::Result<()> {
+        let clock = match &self.shared.clock {
+            Some(clock) => clock,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "sse send timeout requires a clock",
+                ))
+            }
+        };
+        let buf = self.encode_event(event)?;
+        let send = self.inner_send(buf);
+        let sleep = async {
+            clock.sleep(timeout).await;
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "sse send timed out",
+            ))
+        };
+        send.or(sleep).await
+    }
+
+    /// Encode an event into a frame.
+    pub(crate) fn encode_event(&self, event: &Event) -> Result<Vec<u8>, InvalidField> {
+        let mut buf = Vec::new();
+        match event {
+            Event::Message(msg) => wire::encode_message(msg, &mut buf)?,
+            Event::Retry(dur) => wire::write_retry(&mut buf, *dur, None, self.shared.retry_unit)?,
+        }
+        Ok(buf)
     }
 
     /// Send a new "retry" message over SSE.
+    ///
+    /// The duration is sent in milliseconds, unless the encoder was built
+    /// with a different [`RetryUnit`].
+    ///
+    /// Like [`Sender::send`], the event is either queued in full or not at all,
+    /// and an invalid id is rejected.
     pub async fn send_retry(&self, dur: Duration, id: Option<&str>) -> io::Result<()> {
-        // Write the id
-        if let Some(id) = id {
-            self.inner_send(format!("id:{}\n", id)).await?;
-        }
+        let mut msg = Vec::new();
+        wire::write_retry(&mut msg, dur, id, self.shared.retry_unit)?;
+        self.inner_send(msg).await
+    }
 
-        // Write the retry section, and end.
-        let dur = dur.as_secs_f64() as u64;
-        let msg = format!("retry:{}\n\n", dur);
-        self.inner_send(msg).await?;
-        Ok(())
+    /// Send a comment over SSE.
+    ///
+    /// Comments are ignored by clients, which makes them useful for
+    /// keep-alives, debugging breadcrumbs, or padding. Text spanning multiple
+    /// lines is sent as one `:` line per line, so it can never end the
+    /// comment early and inject other fields.
+    ///
+    /// Like [`Sender::send`], the comment is either queued in full or not at
+    /// all.
+    pub async fn send_comment(&self, text: &str) -> io::Result<()> {
+        let mut msg = Vec::new();
+        wire::encode_comment(text, &mut msg);
+        self.inner_send(msg).await
     }
 }
+
+/// Close the stream when dropped, however the send it guards ends.
+struct CloseOnDrop<'a>(&'a Sender);
+
+impl Drop for CloseOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// The error returned when the encoder has gone away.
+fn disconnected() -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionAborted, "sse disconnected")
+}
@@ -3,7 +3,7 @@ use crate::Message;
 use std::time::Duration;
 
 /// The kind of SSE event sent.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Event {
     /// A retry frame, signaling a new retry duration must be used..
     Retry(Duration),
@@ -17,24 +17,54 @@ impl Event {
         Self::Message(Message { name, data, id })
     }
 
-    /// Create a new retry.
-    pub(crate) fn new_retry(dur: u64) -> Self {
-        Self::Retry(Duration::from_secs_f64(dur as f64))
-    }
-
     /// Check whether this is a Retry variant.
     pub fn is_retry(&self) -> bool {
-        match *self {
-            Self::Retry(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::Retry(_))
     }
 
     /// Check whether this is a `Message` variant.
     pub fn is_message(&self) -> bool {
-        match *self {
-            Self::Message(_) => true,
-            _ => false,
+        matches!(self, Self::Message(_))
+    }
+}
+
+impl From<Message> for Event {
+    fn from(msg: Message) -> Self {
+        Self::Message(msg)
+    }
+}
+
+/// The unit of the `retry` field.
+///
+/// The spec defines the reconnection time in milliseconds, which is the
+/// default. Versions 5.1 and earlier of this crate sent and read the field in
+/// whole seconds; `RetryUnit::Seconds` keeps that behavior for peers which
+/// still rely on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RetryUnit {
+    /// Milliseconds, as the spec requires.
+    #[default]
+    Milliseconds,
+    /// Whole seconds, as sent and read by async-sse 5.1 and earlier.
+    ///
+    /// Durations are truncated to whole seconds when encoding.
+    Seconds,
+}
+
+impl RetryUnit {
+    /// Interpret the value of a `retry` field.
+    pub(crate) fn to_duration(self, value: u64) -> Duration {
+        match self {
+            Self::Milliseconds => Duration::from_millis(value),
+            Self::Seconds => Duration::from_secs(value),
+        }
+    }
+
+    /// Convert a duration into the value of a `retry` field.
+    pub(crate) fn to_value(self, dur: Duration) -> u128 {
+        match self {
+            Self::Milliseconds => dur.as_millis(),
+            Self::Seconds => dur.as_secs().into(),
         }
     }
 }
@@ -0,0 +1,173 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+
+/// The error returned when an event name or id can't be sent as is.
+///
+/// A line break would end the field early and let the rest of the value
+/// inject arbitrary fields into the stream. An id containing U+0000 NULL
+/// would be ignored by clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidField {
+    field: &'static str,
+    byte: u8,
+}
+
+impl InvalidField {
+    /// The name of the field which was rejected: `"event"` or `"id"`.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+
+    /// The byte which caused the field to be rejected.
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+}
+
+impl fmt::Display for InvalidField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sse {} field contains an invalid character {:?}",
+            self.field,
+            char::from(self.byte)
+        )
+    }
+}
+
+impl Error for InvalidField {}
+
+impl From<InvalidField> for io::Error {
+    fn from(err: InvalidField) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// Check a field value, returning the first byte which can't be sent.
+fn check(field: &'static str, value: &str, forbid_null: bool) -> Result<(), InvalidField> {
+    let invalid = value
+        .bytes()
+        .find(|b| matches!(b, b'\r' | b'\n') || (forbid_null && *b == b'\0'));
+    match invalid {
+        Some(byte) => Err(InvalidField { field, byte }),
+        None => Ok(()),
+    }
+}
+
+/// Replace every byte which can't be sent with a backslash escape.
+///
+/// Backslashes are escaped too, so distinct values stay distinct.
+fn escape(value: &str, forbid_null: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            '\0' if forbid_null => escaped.push_str("\\0"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Check an event name.
+pub(crate) fn check_name(name: &str) -> Result<(), InvalidField> {
+    check("event", name, false)
+}
+
+/// Check an event id.
+pub(crate) fn check_id(id: &str) -> Result<(), InvalidField> {
+    check("id", id, true)
+}
+
+macro_rules! field_type {
+    ($(#[$meta:meta])* $name:ident, $field:literal, $forbid_null:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            #[doc = concat!("Create a new `", stringify!($name), "`, rejecting values which can't be sent.")]
+            pub fn new(value: impl Into<String>) -> Result<Self, InvalidField> {
+                let value = value.into();
+                check($field, &value, $forbid_null)?;
+                Ok(Self(value))
+            }
+
+            #[doc = concat!("Create a new `", stringify!($name), "`, replacing characters which can't be sent with backslash escapes, and doubling backslashes.")]
+            pub fn escape(value: &str) -> Self {
+                Self(escape(value, $forbid_null))
+            }
+
+            /// Access the value as a string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Convert into the inner string.
+            pub fn into_string(self) -> String {
+                self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = InvalidField;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = InvalidField;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.0
+            }
+        }
+    };
+}
+
+field_type! {
+    /// A validated event name.
+    ///
+    /// Event names can't contain line breaks (CR or LF).
+    EventName, "event", false
+}
+
+field_type! {
+    /// A validated event id.
+    ///
+    /// Event ids can't contain line breaks (CR or LF) or U+0000 NULL.
+    EventId, "id", true
+}
@@ -0,0 +1,76 @@
+use bytes::Bytes;
+
+use std::time::Duration;
+
+use crate::{wire, Event, InvalidField, Message};
+
+/// An encoded SSE event, which can be sent to many encoders without copying.
+///
+/// Encoding an event once and cloning the frame is cheap: clones share the
+/// same bytes. This makes broadcasting a large event to many clients much
+/// cheaper than sending it to every [`Sender`] separately.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{encode, Frame, Message};
+///
+/// # futures_lite::future::block_on(async {
+/// let frame = Frame::from_message(&Message::new("chashu"))?;
+/// let (sender, _encoder) = encode();
+/// sender.send_frame(&frame).await?;
+/// # http_types::Result::Ok(())
+/// # });
+/// ```
+///
+/// [`Sender`]: crate::Sender
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Frame(Bytes);
+
+impl Frame {
+    /// Encode an [`Event`].
+    ///
+    /// Retries are encoded in milliseconds.
+    pub fn new(event: &Event) -> Result<Self, InvalidField> {
+        let mut buf = Vec::new();
+        wire::encode_event(event, &mut buf)?;
+        Ok(Self(buf.into()))
+    }
+
+    /// Encode a [`Message`].
+    pub fn from_message(msg: &Message) -> Result<Self, InvalidField> {
+        let mut buf = Vec::new();
+        wire::encode_message(msg, &mut buf)?;
+        Ok(Self(buf.into()))
+    }
+
+    /// Encode a retry, in milliseconds.
+    pub fn from_retry(dur: Duration) -> Self {
+        let mut buf = Vec::new();
+        wire::encode_retry(dur, &mut buf);
+        Self(buf.into())
+    }
+
+    /// Encode a comment.
+    pub fn from_comment(text: &str) -> Self {
+        let mut buf = Vec::new();
+        wire::encode_comment(text, &mut buf);
+        Self(buf.into())
+    }
+
+    /// Access the wire bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Convert into the shared wire bytes.
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Frame {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
@@ -1,50 +1,86 @@
-//! Async Server Sent Event parser and encoder.
-//!
-//! # Example
-//!
-//! ```
-//! use async_sse::{decode, encode, Event};
-//! use async_std::prelude::*;
-//! use async_std::io::BufReader;
-//! use async_std::task;
-//!
-//! #[async_std::main]
-//! async fn main() -> http_types::Result<()> {
-//!     // Create an encoder + sender pair and send a message.
-//!     let (sender, encoder) = encode();
-//!     task::spawn(async move {
-//!         sender.send("cat", "chashu", None).await;
-//!     });
-//!
-//!     // Decode messages using a decoder.
-//!     let mut reader = decode(BufReader::new(encoder));
-//!     let event = reader.next().await.unwrap()?;
-//!     // Match and handle the event
-//!
-//!     # let _ = event;
-//!     Ok(())
-//! }
-//! ```
-//!
-//! # References
-//!
-//! - [SSE Spec](https://html.spec.whatwg.org/multipage/server-sent-events.html#concept-event-stream-last-event-id)
-//! - [EventSource web platform tests](https://github.com/web-platform-tests/wpt/tree/master/eventsource)
-
-#![deny(missing_debug_implementations, nonstandard_style)]
-#![warn(missing_docs, rust_2018_idioms, unreachable_pub)]
-
-mod decoder;
-mod encoder;
-mod event;
-mod handshake;
-mod lines;
-mod message;
-
-pub use decoder::{decode, Decoder};
-pub use encoder::{encode, Encoder, Sender};
-pub use event::Event;
-pub use handshake::upgrade;
-pub use message::Message;
-
-pub(crate) use lines::Lines;
+//! Async Server Sent Event parser and encoder.
+//!
+//! # Example
+//!
+//! ```
+//! use async_sse::{decode, encode, Event};
+//! use async_std::prelude::*;
+//! use async_std::io::BufReader;
+//! use async_std::task;
+//!
+//! #[async_std::main]
+//! async fn main() -> http_types::Result<()> {
+//!     // Create an encoder + sender pair and send a message.
+//!     let (sender, encoder) = encode();
+//!     task::spawn(async move {
+//!         sender.send("cat", "chashu", None).await;
+//!     });
+//!
+//!     // Decode messages using a decoder.
+//!     let mut reader = decode(BufReader::new(encoder));
+//!     let event = reader.next().await.unwrap()?;
+//!     // Match and handle the event
+//!
+//!     # let _ = event;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! # Blocking I/O
+//!
+//! [`decode_blocking`] and [`BlockingWriter`] read and write SSE over
+//! `std::io`, for code which doesn't run on an async runtime. They share the
+//! parser and encoder with their async counterparts.
+//!
+//! # Features
+//!
+//! - `bytes`: queue frames as reference counted `bytes::Bytes`, and add
+//!   `Frame` and `Sender::send_frame` to broadcast an event to many encoders
+//!   without copying it.
+//!
+//! # References
+//!
+//! - [SSE Spec](https://html.spec.whatwg.org/multipage/server-sent-events.html#concept-event-stream-last-event-id)
+//! - [EventSource web platform tests](https://github.com/web-platform-tests/wpt/tree/master/eventsource)
+
+#![deny(missing_debug_implementations, nonstandard_style)]
+#![warn(missing_docs, rust_2018_idioms, unreachable_pub)]
+
+mod blocking;
+mod clock;
+mod decoder;
+mod encoder;
+mod event;
+mod field;
+#[cfg(feature = "bytes")]
+mod frame;
+mod handshake;
+mod limit;
+mod lines;
+mod message;
+mod parser;
+mod sink;
+mod stream_encoder;
+mod wire;
+mod writer;
+
+pub use blocking::{decode_blocking, BlockingDecoder, BlockingWriter};
+#[cfg(feature = "async-io")]
+pub use clock::SystemClock;
+pub use clock::{Clock, Sleep};
+pub use decoder::{decode, decode_with_state, Decoder, DecoderBuilder, DecoderState};
+pub use encoder::{encode, Backpressure, Encoder, EncoderBuilder, Sender, TrySendError};
+pub use event::{Event, RetryUnit};
+pub use field::{EventId, EventName, InvalidField};
+#[cfg(feature = "bytes")]
+pub use frame::Frame;
+pub use handshake::upgrade;
+pub use limit::{Limit, LimitExceeded};
+pub use message::Message;
+pub use sink::SenderSink;
+pub use stream_encoder::{encode_stream, StreamEncoder};
+pub use wire::{encode_comment, encode_event, encode_message, encode_retry};
+pub use writer::SseWriter;
+
+pub(crate) use lines::Lines;
+pub(crate) use parser::Parser;
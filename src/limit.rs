@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// A decoder memory limit.
+///
+/// See [`DecoderBuilder`](crate::DecoderBuilder) to configure the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// The maximum length of a line, in bytes.
+    LineLength,
+    /// The maximum size of an event's data, in bytes.
+    EventSize,
+    /// The maximum length of an event id, in bytes.
+    IdLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Limit::LineLength => "line",
+            Limit::EventSize => "event",
+            Limit::IdLength => "id",
+        })
+    }
+}
+
+/// The error returned when the decoder hits one of its memory limits.
+///
+/// The decoder reports it as an [`io::Error`] of kind
+/// [`InvalidData`](io::ErrorKind::InvalidData), and skips the event it was
+/// decoding.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{decode_blocking, DecoderBuilder, Limit, LimitExceeded};
+///
+/// let mut decoder = DecoderBuilder::new()
+///     .max_line_len(8)
+///     .build_blocking(&b"data:chashu\n\n"[..]);
+/// let err = decoder.next().unwrap().unwrap_err();
+/// let err = err.downcast_ref::<std::io::Error>().unwrap();
+/// let err = err.get_ref().unwrap().downcast_ref::<LimitExceeded>().unwrap();
+/// assert_eq!(err.limit(), Limit::LineLength);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+    limit: Limit,
+    max: usize,
+}
+
+impl LimitExceeded {
+    pub(crate) fn new(limit: Limit, max: usize) -> Self {
+        Self { limit, max }
+    }
+
+    /// The limit which was exceeded.
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// The configured maximum, in bytes.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sse {} exceeds the limit of {} bytes",
+            self.limit, self.max
+        )
+    }
+}
+
+impl Error for LimitExceeded {}
+
+impl From<LimitExceeded> for io::Error {
+    fn from(err: LimitExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Check whether an error was caused by a limit being exceeded.
+pub(crate) fn is_limit_exceeded(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|err| err.is::<LimitExceeded>())
+}
@@ -1,7 +1,6 @@
-use std::io;
+use std::io::{self, BufRead};
 use std::mem;
 use std::pin::Pin;
-use std::str;
 
 use pin_project_lite::pin_project;
 
@@ -9,6 +8,8 @@ use futures_lite::prelude::*;
 use futures_lite::ready;
 use std::task::{Context, Poll};
 
+use crate::{Limit, LimitExceeded};
+
 pin_project! {
     /// A stream of lines in a byte stream.
     ///
@@ -23,23 +24,16 @@ pin_project! {
     pub(crate) struct Lines<R> {
         #[pin]
         pub(crate) reader: R,
-        pub(crate) buf: String,
-        pub(crate) bytes: Vec<u8>,
-        pub(crate) read: usize,
+        pub(crate) buf: LineBuf,
     }
 }
 
 impl<R> Lines<R> {
-    pub(crate) fn new(reader: R) -> Lines<R>
+    pub(crate) fn new(reader: R, buf: LineBuf) -> Lines<R>
     where
         R: AsyncBufRead + Unpin + Sized,
     {
-        Lines {
-            reader,
-            buf: String::new(),
-            bytes: Vec::new(),
-            read: 0,
-        }
+        Lines { reader, buf }
     }
 }
 
@@ -47,76 +41,160 @@ impl<R: AsyncBufRead> Stream for Lines<R> {
     type Item = io::Result<String>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
-        let n = ready!(read_line_internal(
-            this.reader,
-            cx,
-            this.buf,
-            this.bytes,
-            this.read
-        ))?;
-        if n == 0 && this.buf.is_empty() {
-            return Poll::Ready(None);
-        }
-        if this.buf.ends_with('\n') {
-            this.buf.pop();
-        }
-        if this.buf.ends_with('\r') {
-            this.buf.pop();
+        let mut this = self.project();
+        loop {
+            let (used, scanned) = {
+                let available = match ready!(this.reader.as_mut().poll_fill_buf(cx)) {
+                    Ok(available) => available,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                };
+                this.buf.scan(available)
+            };
+            this.reader.as_mut().consume(used);
+            match scanned {
+                Scanned::More => continue,
+                Scanned::Line(line) => return Poll::Ready(Some(line)),
+                Scanned::Eof => return Poll::Ready(None),
+            }
         }
-        Poll::Ready(Some(Ok(mem::replace(this.buf, String::new()))))
     }
 }
 
-fn read_line_internal<R: AsyncBufRead + ?Sized>(
-    reader: Pin<&mut R>,
-    cx: &mut Context<'_>,
-    buf: &mut String,
-    bytes: &mut Vec<u8>,
-    read: &mut usize,
-) -> Poll<io::Result<usize>> {
-    let ret = ready!(read_until_internal(reader, cx, bytes, read));
-    if str::from_utf8(&bytes).is_err() {
-        Poll::Ready(ret.and_then(|_| {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "stream did not contain valid UTF-8",
-            ))
-        }))
-    } else {
-        debug_assert!(buf.is_empty());
-        debug_assert_eq!(*read, 0);
-        // Safety: `bytes` is a valid UTF-8 because `str::from_utf8` returned `Ok`.
-        mem::swap(unsafe { buf.as_mut_vec() }, bytes);
-        Poll::Ready(ret)
+/// The lines of a blocking reader, split the same way as [`Lines`].
+#[derive(Debug)]
+pub(crate) struct BlockingLines<R> {
+    pub(crate) reader: R,
+    pub(crate) buf: LineBuf,
+}
+
+impl<R: BufRead> BlockingLines<R> {
+    pub(crate) fn new(reader: R, buf: LineBuf) -> Self {
+        Self { reader, buf }
     }
 }
 
-fn read_until_internal<R: AsyncBufRead + ?Sized>(
-    mut reader: Pin<&mut R>,
-    cx: &mut Context<'_>,
-    buf: &mut Vec<u8>,
-    read: &mut usize,
-) -> Poll<io::Result<usize>> {
-    loop {
-        let (done, used) = {
-            let available = ready!(reader.as_mut().poll_fill_buf(cx))?;
-            if let Some(i) = memchr::memchr2(b'\r', b'\n', available) {
-                buf.extend_from_slice(&available[..=i]);
-                // Remove any tailing \r or \n characters.
-                match available.get(i + 1) {
-                    Some(c) if available[i] == b'\r' && *c == b'\n' => (true, i + 2),
-                    _ => (true, i + 1),
-                }
-            } else {
-                buf.extend_from_slice(available);
-                (false, available.len())
+impl<R: BufRead> Iterator for BlockingLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (used, scanned) = match self.reader.fill_buf() {
+                Ok(available) => self.buf.scan(available),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            self.reader.consume(used);
+            match scanned {
+                Scanned::More => continue,
+                Scanned::Line(line) => return Some(line),
+                Scanned::Eof => return None,
             }
+        }
+    }
+}
+
+/// The outcome of scanning a chunk of input.
+pub(crate) enum Scanned {
+    /// No line ending was found yet.
+    More,
+    /// A line was completed, or rejected.
+    Line(io::Result<String>),
+    /// The input ended.
+    Eof,
+}
+
+/// The line splitting state shared by [`Lines`] and [`BlockingLines`].
+#[derive(Debug, Default)]
+pub(crate) struct LineBuf {
+    /// The line read so far.
+    bytes: Vec<u8>,
+    /// Reject invalid UTF-8 instead of replacing it.
+    strict_utf8: bool,
+    /// The maximum length of a line, without its line ending.
+    max_len: Option<usize>,
+    /// Are we dropping the rest of a line which was too long?
+    discarding: bool,
+    /// Did the last line end with a CR at the end of the input so far? If the
+    /// next byte is a LF, it belongs to the same CRLF line ending.
+    after_cr: bool,
+}
+
+impl LineBuf {
+    pub(crate) fn new(strict_utf8: bool, max_len: Option<usize>) -> Self {
+        Self {
+            strict_utf8,
+            max_len,
+            ..Self::default()
+        }
+    }
+
+    /// Consume bytes up to and including the next line ending in `available`.
+    ///
+    /// An empty `available` marks the end of the input. Returns how many bytes
+    /// were used.
+    pub(crate) fn scan(&mut self, available: &[u8]) -> (usize, Scanned) {
+        if mem::replace(&mut self.after_cr, false) && available.first() == Some(&b'\n') {
+            return (1, Scanned::More);
+        }
+        if available.is_empty() {
+            self.discarding = false;
+            if self.bytes.is_empty() {
+                return (0, Scanned::Eof);
+            }
+            return (0, Scanned::Line(self.finish()));
+        }
+
+        let (end, used) = match memchr::memchr2(b'\r', b'\n', available) {
+            // Remove any tailing \r or \n characters.
+            Some(i) => match available.get(i + 1) {
+                Some(c) if available[i] == b'\r' && *c == b'\n' => (Some(i), i + 2),
+                None if available[i] == b'\r' => {
+                    self.after_cr = true;
+                    (Some(i), i + 1)
+                }
+                _ => (Some(i), i + 1),
+            },
+            None => (None, available.len()),
         };
-        reader.as_mut().consume(used);
-        *read += used;
-        if done || used == 0 {
-            return Poll::Ready(Ok(mem::replace(read, 0)));
+        let content = &available[..end.unwrap_or(used)];
+
+        if self.discarding {
+            self.discarding = end.is_none();
+            return (used, Scanned::More);
+        }
+        if let Some(max) = self.max_len {
+            if self.bytes.len() + content.len() > max {
+                // Report the line right away, and drop the rest of it as it
+                // comes in.
+                self.bytes = Vec::new();
+                self.discarding = end.is_none();
+                let err = LimitExceeded::new(Limit::LineLength, max);
+                return (used, Scanned::Line(Err(err.into())));
+            }
+        }
+
+        self.bytes.extend_from_slice(content);
+        match end {
+            Some(_) => (used, Scanned::Line(self.finish())),
+            None => (used, Scanned::More),
+        }
+    }
+
+    /// Convert a complete line to a string.
+    ///
+    /// Invalid UTF-8 is replaced with U+FFFD REPLACEMENT CHARACTER, as the spec
+    /// requires, or rejected in strict mode.
+    fn finish(&mut self) -> io::Result<String> {
+        let line = mem::take(&mut self.bytes);
+        match String::from_utf8(line) {
+            Ok(line) => Ok(line),
+            Err(err) if !self.strict_utf8 => {
+                Ok(String::from_utf8_lossy(err.as_bytes()).into_owned())
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )),
         }
     }
 }
@@ -1,5 +1,16 @@
 /// An SSE event with a data payload.
-#[derive(Debug, PartialEq, Eq, Hash)]
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::Message;
+///
+/// let msg = Message::new("chashu").with_name("cat").with_id("0");
+/// assert_eq!(msg.name(), "cat");
+/// assert_eq!(msg.id().as_deref(), Some("0"));
+/// assert_eq!(msg.data(), b"chashu");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Message {
     /// The ID of this event.
     ///
@@ -12,6 +23,47 @@ pub struct Message {
 }
 
 impl Message {
+    /// Create a new message with the default "message" event name and no id.
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            id: None,
+            name: "message".to_string(),
+            data: data.into(),
+        }
+    }
+
+    /// Set the event name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.set_name(name);
+        self
+    }
+
+    /// Set the message id.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.set_id(id);
+        self
+    }
+
+    /// Set the event name.
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Set the message id.
+    pub fn set_id(&mut self, id: impl Into<String>) {
+        self.id = Some(id.into());
+    }
+
+    /// Remove the message id.
+    pub fn clear_id(&mut self) {
+        self.id = None;
+    }
+
+    /// Replace the event data.
+    pub fn set_data(&mut self, data: impl Into<Vec<u8>>) {
+        self.data = data.into();
+    }
+
     /// Get the message id.
     pub fn id(&self) -> &Option<String> {
         &self.id
@@ -32,3 +84,21 @@ impl Message {
         self.data
     }
 }
+
+impl From<String> for Message {
+    fn from(data: String) -> Self {
+        Self::new(data)
+    }
+}
+
+impl From<&str> for Message {
+    fn from(data: &str) -> Self {
+        Self::new(data)
+    }
+}
+
+impl From<Vec<u8>> for Message {
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(data)
+    }
+}
@@ -0,0 +1,243 @@
+use std::io;
+use std::time::Duration;
+
+use crate::limit::is_limit_exceeded;
+use crate::{DecoderState, Event, Limit, LimitExceeded, RetryUnit};
+
+/// The SSE parsing state machine, shared by the async and blocking decoders.
+///
+/// It's fed one line at a time, with the line ending already removed.
+#[derive(Debug)]
+pub(crate) struct Parser {
+    /// Have we processed the optional Byte Order Marker on the first line?
+    processed_bom: bool,
+    /// The _last event ID_ buffer.
+    last_event_id: Option<String>,
+    /// The _last event ID_ string, updated when an event is dispatched.
+    dispatched_id: Option<String>,
+    /// The _reconnection time_, once the server has set it.
+    reconnection_time: Option<Duration>,
+    /// The _event type_ buffer.
+    event_type: Option<String>,
+    /// The _data_ buffer.
+    data: Vec<u8>,
+    /// The unit of `retry` fields.
+    retry_unit: RetryUnit,
+    /// The maximum size of the data buffer.
+    max_event_size: Option<usize>,
+    /// The maximum length of an event id.
+    max_id_len: Option<usize>,
+    /// Skip events which exceed a limit, without reporting an error.
+    skip_oversized: bool,
+    /// Are we dropping an event which exceeded a limit?
+    skipping: bool,
+}
+
+impl Parser {
+    pub(crate) fn new(
+        retry_unit: RetryUnit,
+        max_event_size: Option<usize>,
+        max_id_len: Option<usize>,
+        skip_oversized: bool,
+        state: DecoderState,
+    ) -> Self {
+        let (last_event_id, reconnection_time) = state.into_parts();
+        Self {
+            processed_bom: false,
+            dispatched_id: last_event_id.clone(),
+            last_event_id,
+            reconnection_time,
+            event_type: None,
+            data: vec![],
+            retry_unit,
+            max_event_size,
+            max_id_len,
+            skip_oversized,
+            skipping: false,
+        }
+    }
+
+    /// The id a reconnecting client should send as `Last-Event-ID`.
+    pub(crate) fn last_event_id(&self) -> Option<&str> {
+        self.dispatched_id.as_deref()
+    }
+
+    /// The reconnection time most recently set by a `retry` field.
+    pub(crate) fn reconnection_time(&self) -> Option<Duration> {
+        self.reconnection_time
+    }
+
+    /// A copy of the state to carry over to the next connection.
+    pub(crate) fn state(&self) -> DecoderState {
+        DecoderState::from_parts(self.dispatched_id.clone(), self.reconnection_time)
+    }
+
+    /// Move the state out, to carry over to the next connection.
+    pub(crate) fn into_state(self) -> DecoderState {
+        DecoderState::from_parts(self.dispatched_id, self.reconnection_time)
+    }
+
+    /// Process a line from the lines decoder, returning an event if it
+    /// completes one.
+    ///
+    /// A line which was too long, or isn't valid UTF-8 in strict mode, skips
+    /// the event it belongs to.
+    pub(crate) fn next_line(&mut self, line: io::Result<String>) -> io::Result<Option<Event>> {
+        match line {
+            Ok(line) => self.parse_line(&line).map_err(Into::into),
+            Err(err) if is_limit_exceeded(&err) => {
+                self.skip_event();
+                if self.skip_oversized {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                self.skip_event();
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Drop the event being decoded, along with the rest of its fields.
+    fn skip_event(&mut self) {
+        self.skipping = true;
+        self.event_type = None;
+        self.data = Vec::new();
+    }
+
+    /// Skip the current event, reporting the error unless in lenient mode.
+    fn exceeded(&mut self, limit: Limit, max: usize) -> Result<Option<Event>, LimitExceeded> {
+        self.skip_event();
+        if self.skip_oversized {
+            Ok(None)
+        } else {
+            Err(LimitExceeded::new(limit, max))
+        }
+    }
+
+    /// Process a line, returning an event if it completes one.
+    fn parse_line(&mut self, line: &str) -> Result<Option<Event>, LimitExceeded> {
+        // Get rid of the BOM at the start of the stream, and only there.
+        let line = match std::mem::replace(&mut self.processed_bom, true) {
+            false => line.strip_prefix('\u{feff}').unwrap_or(line),
+            true => line,
+        };
+
+        log::trace!("> new line: {:?}", line);
+
+        // If the line is empty (a blank line), dispatch the event.
+        if line.is_empty() {
+            log::trace!("> end of frame");
+            // Set the _last event ID_ string to the _last event ID_ buffer.
+            self.dispatched_id.clone_from(&self.last_event_id);
+            if std::mem::replace(&mut self.skipping, false) {
+                log::trace!("> end of skipped frame");
+                return Ok(None);
+            }
+            let event = self.take_message();
+            log::trace!("> end of frame [event]: {:?}", event);
+            return Ok(event);
+        }
+
+        // If the line starts with a U+003A COLON character (:), ignore the line.
+        if line.starts_with(':') {
+            log::trace!("> comment");
+            return Ok(None);
+        }
+
+        // If the line contains a U+003A COLON character (:), the field name is
+        // the part before the first colon, and the field value the part after
+        // it, with a single leading space removed. Otherwise, the whole line is
+        // the field name, and the field value is the empty string.
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, strip_leading_space(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            // Drop the rest of an event which is being skipped, so it can't
+            // change the last event id or reconnection time either.
+            _ if self.skipping => log::trace!("> skipped: {:?}", field),
+            // If the field name is "event":
+            "event" => {
+                log::trace!("> event");
+                // Set the event type buffer to field value.
+                self.event_type = Some(value.to_string());
+            }
+            // If the field name is "data":
+            "data" => {
+                log::trace!("> data: {:?}", value);
+                // The data buffer already holds a LF after every earlier line.
+                // The one after this line is removed on dispatch, so it
+                // doesn't count.
+                if let Some(max) = self.max_event_size {
+                    if self.data.len() + value.len() > max {
+                        return self.exceeded(Limit::EventSize, max);
+                    }
+                }
+                // Append the field value to the data buffer,
+                self.data.extend(value.as_bytes());
+                // then append a single U+000A LINE FEED (LF) character to the data buffer.
+                self.data.push(b'\n');
+            }
+            // If the field name is "id":
+            "id" if !value.contains('\0') => {
+                log::trace!("> id");
+                // If the field value does not contain U+0000 NULL, then set the last event ID buffer to the field value.
+                // Otherwise, ignore the field. An empty value resets the id.
+                if let Some(max) = self.max_id_len {
+                    if value.len() > max {
+                        return self.exceeded(Limit::IdLength, max);
+                    }
+                }
+                self.last_event_id = Some(value.to_string()).filter(|id| !id.is_empty());
+            }
+            // If the field name is "retry":
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                log::trace!("> retry");
+                // If the field value consists of only ASCII digits, then interpret the field value
+                // as an integer in base ten, and set the event stream's reconnection time to that
+                // integer. Otherwise, ignore the field.
+                if let Ok(time) = value.parse::<u64>() {
+                    let dur = self.retry_unit.to_duration(time);
+                    self.reconnection_time = Some(dur);
+                    return Ok(Some(Event::Retry(dur)));
+                }
+            }
+            // Otherwise, the field is ignored.
+            _ => log::trace!("> ignored field: {:?}", field),
+        }
+        Ok(None)
+    }
+
+    fn take_message(&mut self) -> Option<Event> {
+        if self.data.is_empty() {
+            // If the data buffer is an empty string, set the data buffer and
+            // the event type buffer to the empty string [and return.]
+            self.event_type.take();
+            None
+        } else {
+            // Removing tailing newlines
+            if self.data.ends_with(b"\n") {
+                self.data.pop();
+            }
+            // An empty event type dispatches as "message".
+            let name = match self.event_type.take() {
+                Some(name) if !name.is_empty() => name,
+                _ => "message".to_string(),
+            };
+            let data = std::mem::take(&mut self.data);
+            // The _last event ID_ buffer persists between messages.
+            let id = self.last_event_id.clone();
+            Some(Event::new_msg(name, data, id))
+        }
+    }
+}
+
+/// Remove a leading space (code point 0x20) from a string slice.
+fn strip_leading_space(input: &str) -> &str {
+    input.strip_prefix(' ').unwrap_or(input)
+}
@@ -0,0 +1,141 @@
+use futures_lite::prelude::*;
+use futures_lite::ready;
+use futures_sink::Sink;
+use std::task::{Context, Poll};
+
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+
+use crate::{wire, Event, Message, Sender};
+
+type SendFuture = Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'static>>;
+
+/// A [`Sink`] of events, created by [`Sender::into_sink`].
+///
+/// Accepts both [`Event`]s and [`Message`]s, with the same semantics as
+/// [`Sender::send_event`] and [`Sender::send_message`]. An item counts as
+/// flushed once it's been queued for the [`Encoder`], which means it will be
+/// read in full.
+///
+/// Closing the sink only flushes it. The stream ends once every sender has
+/// been dropped, or [`Sender::close`] has been called.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{decode, encode, Event, Message};
+/// use async_std::io::BufReader;
+/// use futures_util::{stream, StreamExt};
+///
+/// # async_std::task::block_on(async {
+/// let (sender, encoder) = encode();
+/// let events = stream::iter(vec![Ok(Event::from(Message::new("chashu")))]);
+/// async_std::task::spawn(events.forward(sender.into_sink()));
+///
+/// let mut reader = decode(BufReader::new(encoder));
+/// assert!(reader.next().await.unwrap()?.is_message());
+/// # http_types::Result::Ok(())
+/// # });
+/// ```
+///
+/// [`Encoder`]: crate::Encoder
+pub struct SenderSink {
+    sender: Sender,
+    pending: Option<SendFuture>,
+}
+
+impl SenderSink {
+    /// Create a new sink sending to `sender`.
+    pub fn new(sender: Sender) -> Self {
+        Self {
+            sender,
+            pending: None,
+        }
+    }
+
+    /// Access the underlying sender.
+    pub fn get_ref(&self) -> &Sender {
+        &self.sender
+    }
+
+    /// Take back the underlying sender.
+    ///
+    /// An item which hasn't been flushed yet is discarded.
+    pub fn into_inner(self) -> Sender {
+        self.sender
+    }
+
+    /// Drive the pending send, if any, to completion.
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(pending) = self.pending.as_mut() {
+            let res = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            res?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Queue an encoded frame, to be sent by the next poll.
+    fn start_send_frame(&mut self, buf: Vec<u8>) {
+        debug_assert!(self.pending.is_none(), "poll_ready was not called");
+        let sender = self.sender.clone();
+        self.pending = Some(Box::pin(async move { sender.inner_send(buf).await }));
+    }
+}
+
+impl fmt::Debug for SenderSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SenderSink")
+            .field("sender", &self.sender)
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
+
+impl Sink<Event> for SenderSink {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, event: Event) -> io::Result<()> {
+        let this = self.get_mut();
+        let buf = this.sender.encode_event(&event)?;
+        this.start_send_frame(buf);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+}
+
+impl Sink<Message> for SenderSink {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: Message) -> io::Result<()> {
+        let this = self.get_mut();
+        let mut buf = Vec::new();
+        wire::encode_message(&msg, &mut buf)?;
+        this.start_send_frame(buf);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+}
@@ -0,0 +1,134 @@
+use futures_lite::prelude::*;
+use futures_lite::ready;
+use pin_project_lite::pin_project;
+use std::task::{Context, Poll};
+
+use std::io;
+use std::pin::Pin;
+
+use crate::{wire, Event};
+
+/// Create an SSE encoder reading events from a stream.
+///
+/// Unlike [`encode`], this needs no [`Sender`] task: events are pulled from
+/// the stream and encoded as the returned reader is read. Both [`Event`]s and
+/// [`Message`]s can be streamed.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{decode, encode_stream, Message};
+/// use futures_lite::{stream, StreamExt};
+///
+/// # futures_lite::future::block_on(async {
+/// let messages = stream::iter(vec![Message::new("chashu"), Message::new("nori")]);
+/// let mut reader = decode(encode_stream(messages));
+/// assert!(reader.next().await.unwrap()?.is_message());
+/// # http_types::Result::Ok(())
+/// # });
+/// ```
+///
+/// [`encode`]: crate::encode
+/// [`Sender`]: crate::Sender
+/// [`Message`]: crate::Message
+pub fn encode_stream<S>(stream: S) -> StreamEncoder<S>
+where
+    S: Stream,
+    S::Item: Into<Event>,
+{
+    StreamEncoder {
+        stream,
+        buf: Vec::new(),
+        cursor: 0,
+    }
+}
+
+pin_project! {
+    /// An SSE protocol encoder reading events from a stream.
+    ///
+    /// This type is created by [`encode_stream`].
+    ///
+    /// Reading fails with an error of kind `InvalidInput` if an event has an
+    /// invalid name or id.
+    #[derive(Debug)]
+    pub struct StreamEncoder<S> {
+        #[pin]
+        stream: S,
+        buf: Vec<u8>,
+        cursor: usize,
+    }
+}
+
+impl<S> StreamEncoder<S>
+where
+    S: Stream,
+    S::Item: Into<Event>,
+{
+    /// Make sure the current buffer has bytes left to read.
+    ///
+    /// Returns `false` once the stream has ended.
+    fn poll_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<bool>> {
+        let mut this = self.project();
+        // Encode the next event if the current one is exhausted.
+        while this.buf.len() <= *this.cursor {
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(event) => {
+                    this.buf.clear();
+                    *this.cursor = 0;
+                    wire::encode_event(&event.into(), this.buf)?;
+                    log::trace!("> Encoded a new event with len {}", this.buf.len());
+                }
+                None => {
+                    log::trace!("> Encoder done reading");
+                    return Poll::Ready(Ok(false));
+                }
+            }
+        }
+        Poll::Ready(Ok(true))
+    }
+}
+
+impl<S> AsyncRead for StreamEncoder<S>
+where
+    S: Stream,
+    S::Item: Into<Event>,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if !ready!(self.as_mut().poll_buf(cx))? {
+            return Poll::Ready(Ok(0));
+        }
+        let this = self.project();
+
+        // Write the current buffer to completion.
+        let local_buf = &this.buf[*this.cursor..];
+        let max = buf.len().min(local_buf.len());
+        buf[..max].clone_from_slice(&local_buf[..max]);
+        *this.cursor += max;
+
+        // Return bytes read.
+        Poll::Ready(Ok(max))
+    }
+}
+
+impl<S> AsyncBufRead for StreamEncoder<S>
+where
+    S: Stream,
+    S::Item: Into<Event>,
+{
+    fn poll_fill_buf(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        if !ready!(self.as_mut().poll_buf(cx))? {
+            return Poll::Ready(Ok(&[]));
+        }
+        let this = self.project();
+        Poll::Ready(Ok(&this.buf[*this.cursor..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.project();
+        *this.cursor += amt;
+    }
+}
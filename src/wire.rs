@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use crate::field::{self, InvalidField};
+use crate::{Event, Message, RetryUnit};
+
+/// Encode an [`Event`], appending its wire bytes to `buf`.
+///
+/// Messages are encoded like [`encode_message`], and retries like
+/// [`encode_retry`].
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::{encode_event, Event, Message};
+///
+/// let mut buf = Vec::new();
+/// let event = Event::from(Message::new("chashu").with_name("cat"));
+/// encode_event(&event, &mut buf)?;
+/// assert_eq!(buf, b"event:cat\ndata:chashu\n\n");
+/// # Ok::<(), async_sse::InvalidField>(())
+/// ```
+pub fn encode_event(event: &Event, buf: &mut Vec<u8>) -> Result<(), InvalidField> {
+    match event {
+        Event::Message(msg) => encode_message(msg, buf),
+        Event::Retry(dur) => {
+            encode_retry(*dur, buf);
+            Ok(())
+        }
+    }
+}
+
+/// Encode a [`Message`], appending its wire bytes to `buf`.
+///
+/// The data is written as is, even if it isn't valid UTF-8. The event name is
+/// omitted if it's the default "message".
+///
+/// # Errors
+///
+/// Returns an error if the name or id contains a line break, or the id
+/// contains U+0000 NULL. Nothing is written to `buf` in that case.
+pub fn encode_message(msg: &Message, buf: &mut Vec<u8>) -> Result<(), InvalidField> {
+    let name = Some(msg.name().as_str()).filter(|name| *name != "message");
+    write_message(buf, name, msg.data(), msg.id().as_deref())
+}
+
+/// Encode a retry, appending its wire bytes to `buf`.
+///
+/// The duration is written in milliseconds.
+pub fn encode_retry(dur: Duration, buf: &mut Vec<u8>) {
+    write_retry(buf, dur, None, RetryUnit::Milliseconds)
+        .expect("a retry without an id is always valid");
+}
+
+/// Encode a comment, appending its wire bytes to `buf`.
+///
+/// Text spanning multiple lines is written as one `:` line per line.
+pub fn encode_comment(text: &str, buf: &mut Vec<u8>) {
+    for line in split_lines(text.as_bytes()) {
+        buf.push(b':');
+        buf.extend_from_slice(line);
+        buf.push(b'\n');
+    }
+}
+
+/// Write a message frame.
+pub(crate) fn write_message(
+    buf: &mut Vec<u8>,
+    name: Option<&str>,
+    data: &[u8],
+    id: Option<&str>,
+) -> Result<(), InvalidField> {
+    if let Some(name) = name {
+        field::check_name(name)?;
+    }
+    if let Some(id) = id {
+        field::check_id(id)?;
+    }
+
+    // Write the event name
+    if let Some(name) = name {
+        push_field(buf, "event", name.as_bytes());
+    }
+
+    // Write the id
+    if let Some(id) = id {
+        push_field(buf, "id", id.as_bytes());
+    }
+
+    // Write the data section, and end.
+    for line in split_lines(data) {
+        push_field(buf, "data", line);
+    }
+    buf.push(b'\n');
+    Ok(())
+}
+
+/// Write a retry frame.
+pub(crate) fn write_retry(
+    buf: &mut Vec<u8>,
+    dur: Duration,
+    id: Option<&str>,
+    unit: RetryUnit,
+) -> Result<(), InvalidField> {
+    if let Some(id) = id {
+        field::check_id(id)?;
+    }
+
+    // Write the id
+    if let Some(id) = id {
+        push_field(buf, "id", id.as_bytes());
+    }
+
+    // Write the retry section, and end.
+    let dur = unit.to_value(dur);
+    push_field(buf, "retry", dur.to_string().as_bytes());
+    buf.push(b'\n');
+    Ok(())
+}
+
+/// Write a single `name:value` line.
+///
+/// Decoders strip a single leading space from values, so an extra space is
+/// written in front of values which start with one.
+fn push_field(buf: &mut Vec<u8>, name: &str, value: &[u8]) {
+    buf.extend_from_slice(name.as_bytes());
+    buf.push(b':');
+    if value.starts_with(b" ") {
+        buf.push(b' ');
+    }
+    buf.extend_from_slice(value);
+    buf.push(b'\n');
+}
+
+/// Split bytes on every line ending a decoder recognizes: CRLF, CR, and LF.
+///
+/// Unlike `str::lines`, a trailing line ending yields a final empty line.
+fn split_lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = Some(bytes);
+    std::iter::from_fn(move || {
+        let bytes = rest?;
+        match memchr::memchr2(b'\r', b'\n', bytes) {
+            Some(i) => {
+                let end = match bytes.get(i..i + 2) {
+                    Some(b"\r\n") => i + 2,
+                    _ => i + 1,
+                };
+                rest = Some(&bytes[end..]);
+                Some(&bytes[..i])
+            }
+            None => {
+                rest = None;
+                Some(bytes)
+            }
+        }
+    })
+}
@@ -0,0 +1,182 @@
+use futures_lite::prelude::*;
+
+use std::io;
+use std::time::Duration;
+
+use crate::{wire, Event, Message, RetryUnit};
+
+/// An SSE writer which encodes events straight into an [`AsyncWrite`].
+///
+/// Unlike [`encode`], this needs no channel or separate task, and write errors
+/// are reported as they happen.
+///
+/// # Cancellation
+///
+/// If a future returned by one of the `send` methods is dropped while a frame
+/// is only partially written, the rest of that frame is written first by the
+/// next call to a `send` method or [`SseWriter::flush`]. Frames are never
+/// interleaved, but a frame may be held back until then.
+///
+/// # Examples
+///
+/// ```
+/// use async_sse::SseWriter;
+///
+/// # futures_lite::future::block_on(async {
+/// let mut writer = SseWriter::new(Vec::new());
+/// writer.send("cat", "chashu", None).await?;
+/// assert_eq!(writer.get_ref(), b"event:cat\ndata:chashu\n\n");
+/// # std::io::Result::Ok(())
+/// # });
+/// ```
+///
+/// [`encode`]: crate::encode
+#[derive(Debug)]
+pub struct SseWriter<W> {
+    writer: W,
+    /// The frame being written.
+    buf: Vec<u8>,
+    /// How much of `buf` has been written.
+    written: usize,
+    flush_per_event: bool,
+    retry_unit: RetryUnit,
+}
+
+impl<W: AsyncWrite + Unpin> SseWriter<W> {
+    /// Create a new writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: Vec::new(),
+            written: 0,
+            flush_per_event: true,
+            retry_unit: RetryUnit::default(),
+        }
+    }
+
+    /// Set whether the underlying writer is flushed after every event.
+    ///
+    /// Defaults to `true`, so events reach clients right away. Disable this to
+    /// batch events, and call [`SseWriter::flush`] instead.
+    pub fn flush_per_event(mut self, flush: bool) -> Self {
+        self.flush_per_event = flush;
+        self
+    }
+
+    /// Set the unit `retry` fields are sent in.
+    ///
+    /// Defaults to [`RetryUnit::Milliseconds`], as the spec requires.
+    pub fn retry_unit(mut self, unit: RetryUnit) -> Self {
+        self.retry_unit = unit;
+        self
+    }
+
+    /// Send a new message.
+    ///
+    /// See [`Sender::send`] for how the fields are encoded and validated.
+    ///
+    /// [`Sender::send`]: crate::Sender::send
+    pub async fn send(
+        &mut self,
+        name: impl Into<Option<&str>>,
+        data: &str,
+        id: Option<&str>,
+    ) -> io::Result<()> {
+        self.write_pending().await?;
+        wire::write_message(&mut self.buf, name.into(), data.as_bytes(), id)?;
+        self.write_frame().await
+    }
+
+    /// Send a [`Message`].
+    ///
+    /// See [`Sender::send_message`].
+    ///
+    /// [`Sender::send_message`]: crate::Sender::send_message
+    pub async fn send_message(&mut self, msg: &Message) -> io::Result<()> {
+        self.write_pending().await?;
+        wire::encode_message(msg, &mut self.buf)?;
+        self.write_frame().await
+    }
+
+    /// Send an [`Event`].
+    ///
+    /// See [`Sender::send_event`].
+    ///
+    /// [`Sender::send_event`]: crate::Sender::send_event
+    pub async fn send_event(&mut self, event: &Event) -> io::Result<()> {
+        match event {
+            Event::Message(msg) => self.send_message(msg).await,
+            Event::Retry(dur) => self.send_retry(*dur, None).await,
+        }
+    }
+
+    /// Send a new "retry" message.
+    ///
+    /// See [`Sender::send_retry`].
+    ///
+    /// [`Sender::send_retry`]: crate::Sender::send_retry
+    pub async fn send_retry(&mut self, dur: Duration, id: Option<&str>) -> io::Result<()> {
+        self.write_pending().await?;
+        wire::write_retry(&mut self.buf, dur, id, self.retry_unit)?;
+        self.write_frame().await
+    }
+
+    /// Send a comment.
+    ///
+    /// See [`Sender::send_comment`].
+    ///
+    /// [`Sender::send_comment`]: crate::Sender::send_comment
+    pub async fn send_comment(&mut self, text: &str) -> io::Result<()> {
+        self.write_pending().await?;
+        wire::encode_comment(text, &mut self.buf);
+        self.write_frame().await
+    }
+
+    /// Finish writing any partially written frame, and flush the writer.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.write_pending().await?;
+        self.writer.flush().await
+    }
+
+    /// Access the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Mutably access the underlying writer.
+    ///
+    /// Writing to it directly can corrupt the stream.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Take back the underlying writer.
+    ///
+    /// A partially written frame is discarded.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write the frame in `buf`, and flush if configured to.
+    async fn write_frame(&mut self) -> io::Result<()> {
+        self.write_pending().await?;
+        if self.flush_per_event {
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Write whatever is left of the frame in `buf`.
+    async fn write_pending(&mut self) -> io::Result<()> {
+        while self.written < self.buf.len() {
+            let n = self.writer.write(&self.buf[self.written..]).await?;
+            if n == 0 {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            self.written += n;
+        }
+        self.buf.clear();
+        self.written = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use async_sse::{Backpressure, Encoder, EncoderBuilder, Sender, TrySendError};
+use futures_lite::future;
+use std::io;
+
+use common::assert_events;
+
+/// Create an encoder with room for two events.
+fn encoder(backpressure: Backpressure) -> (Sender, Encoder) {
+    EncoderBuilder::new()
+        .capacity(2)
+        .backpressure(backpressure)
+        .build()
+}
+
+/// Fill the queue, then send two more events.
+async fn overfill(sender: &Sender) -> Vec<io::Result<()>> {
+    let mut results = vec![];
+    for data in &["1", "2", "3", "4"] {
+        results.push(sender.send(None, data, None).await);
+    }
+    results
+}
+
+#[async_std::test]
+async fn block() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::Block);
+    sender.send(None, "1", None).await?;
+    sender.send(None, "2", None).await?;
+    assert!(future::poll_once(sender.send(None, "3", None))
+        .await
+        .is_none());
+    assert_eq!(sender.dropped_events(), 0);
+    drop(sender);
+    assert_events(encoder, &["1", "2"]).await
+}
+
+#[async_std::test]
+async fn drop_oldest() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::DropOldest);
+    for res in overfill(&sender).await {
+        res?;
+    }
+    assert_eq!(sender.dropped_events(), 2);
+    drop(sender);
+    assert_events(encoder, &["3", "4"]).await
+}
+
+#[async_std::test]
+async fn drop_newest() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::DropNewest);
+    for res in overfill(&sender).await {
+        res?;
+    }
+    assert_eq!(sender.dropped_events(), 2);
+    drop(sender);
+    assert_events(encoder, &["1", "2"]).await
+}
+
+#[async_std::test]
+async fn fail() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::Fail);
+    let results = overfill(&sender).await;
+    assert!(results[0].is_ok() && results[1].is_ok());
+    for res in &results[2..] {
+        let err = res.as_ref().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        let err = err.get_ref().unwrap();
+        let err = err.downcast_ref::<TrySendError<()>>().unwrap();
+        assert!(err.is_full());
+    }
+    assert_eq!(sender.dropped_events(), 0);
+    drop(sender);
+    assert_events(encoder, &["1", "2"]).await
+}
+
+#[async_std::test]
+async fn disconnect() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::Disconnect);
+    let results = overfill(&sender).await;
+    assert!(results[0].is_ok() && results[1].is_ok());
+    for res in &results[2..] {
+        assert_eq!(
+            res.as_ref().unwrap_err().kind(),
+            io::ErrorKind::ConnectionAborted
+        );
+    }
+    assert_eq!(sender.dropped_events(), 1);
+    // The stream ends after the queued events, even though the sender lives.
+    assert_events(encoder, &["1", "2"]).await?;
+    drop(sender);
+    Ok(())
+}
+
+#[async_std::test]
+async fn dropped_events_are_shared_by_clones() -> http_types::Result<()> {
+    let (sender, encoder) = encoder(Backpressure::DropNewest);
+    let clone = sender.clone();
+    overfill(&sender).await;
+    overfill(&clone).await;
+    assert_eq!(sender.dropped_events(), 6);
+    assert_eq!(clone.dropped_events(), 6);
+    drop((sender, clone));
+    assert_events(encoder, &["1", "2"]).await
+}
+
+#[async_std::test]
+async fn capacity() -> http_types::Result<()> {
+    let (sender, encoder) = EncoderBuilder::new()
+        .capacity(16)
+        .backpressure(Backpressure::Fail)
+        .build();
+    for n in 0..16 {
+        sender.send(None, &n.to_string(), None).await?;
+    }
+    assert!(sender.send(None, "16", None).await.is_err());
+    drop(sender);
+    let expected: Vec<String> = (0..16).map(|n| n.to_string()).collect();
+    let expected: Vec<&str> = expected.iter().map(|s| s.as_str()).collect();
+    assert_events(encoder, &expected).await
+}
+
+#[test]
+#[should_panic(expected = "capacity cannot be zero")]
+fn zero_capacity() {
+    EncoderBuilder::new().capacity(0);
+}
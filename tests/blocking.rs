@@ -0,0 +1,113 @@
+use async_sse::{
+    decode, decode_blocking, BlockingWriter, DecoderBuilder, Event, Message, SseWriter,
+};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+use proptest::prelude::*;
+use std::io;
+use std::time::Duration;
+
+/// Decode `input` with the async decoder, reading `capacity` bytes at a time.
+fn decode_async(input: &[u8], capacity: usize) -> Vec<Result<Event, String>> {
+    task::block_on(async {
+        let reader = decode(BufReader::with_capacity(capacity, input));
+        reader
+            .map(|event| event.map_err(|e| e.to_string()))
+            .collect()
+            .await
+    })
+}
+
+/// Decode `input` with the blocking decoder, reading `capacity` bytes at a time.
+fn decode_sync(input: &[u8], capacity: usize) -> Vec<Result<Event, String>> {
+    let reader = io::BufReader::with_capacity(capacity, input);
+    decode_blocking(reader)
+        .map(|event| event.map_err(|e| e.to_string()))
+        .collect()
+}
+
+#[test]
+fn decodes_events() -> http_types::Result<()> {
+    let input = b"\xEF\xBB\xBFevent:cat\nid:1\ndata:chashu\r\ndata: nori\r\rretry:3000\n\n:comment\ndata\n\n";
+    let events = decode_blocking(&input[..]).collect::<http_types::Result<Vec<_>>>()?;
+    assert_eq!(
+        events,
+        vec![
+            Event::from(Message::new("chashu\nnori").with_name("cat").with_id("1")),
+            Event::Retry(Duration::from_secs(3)),
+            Event::from(Message::new("").with_id("1")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reports_io_errors() {
+    let mut decoder = DecoderBuilder::new()
+        .strict_utf8(true)
+        .build_blocking(&b"data:\xFF\n\n"[..]);
+    assert!(decoder.next().unwrap().is_err());
+}
+
+#[test]
+fn writer_matches_async_writer() -> http_types::Result<()> {
+    let mut sync = BlockingWriter::new(Vec::new());
+    sync.send("cat", "chashu\nnori", Some("1"))?;
+    sync.send_comment("keep alive")?;
+    sync.send_retry(Duration::from_secs(12), None)?;
+    sync.send_message(&Message::new(" sesame"))?;
+
+    let expected = task::block_on(async {
+        let mut writer = SseWriter::new(Vec::new());
+        writer.send("cat", "chashu\nnori", Some("1")).await?;
+        writer.send_comment("keep alive").await?;
+        writer.send_retry(Duration::from_secs(12), None).await?;
+        writer.send_message(&Message::new(" sesame")).await?;
+        io::Result::Ok(writer.into_inner())
+    })?;
+    assert_eq!(sync.into_inner(), expected);
+    Ok(())
+}
+
+#[test]
+fn writer_round_trips() -> http_types::Result<()> {
+    let mut writer = BlockingWriter::new(Vec::new());
+    writer.send("cat", "chashu", None)?;
+    writer.send_event(&Event::Retry(Duration::from_millis(1500)))?;
+
+    let buf = writer.into_inner();
+    let events = decode_blocking(&buf[..]).collect::<http_types::Result<Vec<_>>>()?;
+    assert_eq!(
+        events,
+        vec![
+            Event::from(Message::new("chashu").with_name("cat")),
+            Event::Retry(Duration::from_millis(1500)),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn writer_rejects_invalid_fields() {
+    let mut writer = BlockingWriter::new(Vec::new());
+    let err = writer.send(None, "chashu", Some("1\n2")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(writer.get_ref().is_empty());
+}
+
+proptest! {
+    #[test]
+    fn decoders_agree(input in any::<Vec<u8>>(), capacity in 1usize..16) {
+        prop_assert_eq!(decode_sync(&input, capacity), decode_async(&input, capacity));
+    }
+
+    #[test]
+    fn decoders_agree_on_sse(
+        lines in proptest::collection::vec("(event|data|id|retry|)(:? ?[a-z0-9 ]{0,4})?(\r\n|\r|\n)", 0..16),
+        capacity in 1usize..16,
+    ) {
+        let input = lines.concat().into_bytes();
+        prop_assert_eq!(decode_sync(&input, capacity), decode_async(&input, capacity));
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "bytes")]
+
+use async_sse::{decode, encode, Event, Frame, Message};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use futures_lite::AsyncBufReadExt;
+use std::time::Duration;
+
+#[async_std::test]
+async fn broadcast_frame() -> http_types::Result<()> {
+    let msg = Message::new("chashu").with_name("cat").with_id("0");
+    let frame = Frame::from_message(&msg)?;
+
+    let mut encoders = vec![];
+    for _ in 0..4 {
+        let (sender, encoder) = encode();
+        sender.send_frame(&frame).await?;
+        encoders.push(encoder);
+    }
+    for encoder in encoders {
+        let mut reader = decode(BufReader::new(encoder));
+        assert_eq!(reader.next().await.unwrap()?, Event::Message(msg.clone()));
+        assert!(reader.next().await.is_none());
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn frames_are_not_copied() -> http_types::Result<()> {
+    let frame = Frame::from_message(&Message::new("x".repeat(4096)))?;
+    let (sender, mut encoder) = encode();
+    sender.send_frame(&frame).await?;
+
+    let buf = encoder.fill_buf().await?;
+    assert_eq!(buf.as_ptr(), frame.as_bytes().as_ptr());
+    assert_eq!(buf.len(), frame.as_bytes().len());
+    Ok(())
+}
+
+#[test]
+fn frame_bytes() -> http_types::Result<()> {
+    let event = Event::Retry(Duration::from_millis(1500));
+    assert_eq!(Frame::new(&event)?.as_bytes(), b"retry:1500\n\n");
+    assert_eq!(Frame::from_comment("hi").as_bytes(), b":hi\n");
+    let msg = Message::new("chashu").with_id("0\n");
+    assert!(Frame::from_message(&msg).is_err());
+    Ok(())
+}
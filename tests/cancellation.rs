@@ -0,0 +1,67 @@
+mod common;
+
+use async_sse::{encode, Encoder, Sender};
+use async_std::task;
+use futures_lite::future;
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use common::assert_events;
+
+/// How many times a pending send is polled before it is dropped.
+const MAX_POLLS: usize = 4;
+
+/// Poll a future `polls` times, asserting it never completes, then drop it.
+async fn cancel_after<F: Future>(fut: F, polls: usize) {
+    let mut fut = Box::pin(fut);
+    for _ in 0..polls {
+        assert!(future::poll_once(&mut fut).await.is_none());
+    }
+}
+
+/// Create an encoder whose queue is full, so the next send has to wait.
+async fn full_encoder() -> io::Result<(Sender, Encoder)> {
+    let (sender, encoder) = encode();
+    sender.send("cat", "chashu", None).await?;
+    Ok((sender, encoder))
+}
+
+#[async_std::test]
+async fn cancelled_send_is_not_written() -> http_types::Result<()> {
+    for polls in 0..=MAX_POLLS {
+        let (sender, encoder) = full_encoder().await?;
+        cancel_after(sender.send("dog", "a\nb\nc", Some("1")), polls).await;
+        task::spawn(async move { sender.send("cat", "nori", None).await });
+        assert_events(encoder, &["chashu", "nori"]).await?;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn cancelled_send_retry_is_not_written() -> http_types::Result<()> {
+    for polls in 0..=MAX_POLLS {
+        let (sender, encoder) = full_encoder().await?;
+        let dur = Duration::from_secs(12);
+        cancel_after(sender.send_retry(dur, Some("1")), polls).await;
+        task::spawn(async move { sender.send("cat", "nori", None).await });
+        assert_events(encoder, &["chashu", "nori"]).await?;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn cancelled_send_does_not_affect_clones() -> http_types::Result<()> {
+    for polls in 0..=MAX_POLLS {
+        let (sender, encoder) = full_encoder().await?;
+        let clone = sender.clone();
+        cancel_after(sender.send("dog", "a\nb", None), polls).await;
+        drop(sender);
+        task::spawn(async move {
+            clone.send("cat", "nori", None).await?;
+            clone.send("cat", "mochi", None).await
+        });
+        assert_events(encoder, &["chashu", "nori", "mochi"]).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use async_sse::{decode, EncoderBuilder, Event};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+use futures_lite::future;
+use std::io;
+use std::time::Duration;
+
+#[async_std::test]
+async fn close_drains_queued_events() -> http_types::Result<()> {
+    let (sender, encoder) = EncoderBuilder::new().capacity(4).build();
+    let clone = sender.clone();
+    sender.send(None, "chashu", None).await?;
+    clone.send(None, "nori", None).await?;
+
+    assert!(sender.close());
+    assert!(!clone.close());
+    assert!(sender.is_closed() && clone.is_closed());
+    let err = clone.send(None, "mochi", None).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+
+    // The stream ends after the queued events, while both senders are alive.
+    let mut reader = decode(BufReader::new(encoder));
+    for data in &["chashu", "nori"] {
+        match reader.next().await.unwrap()? {
+            Event::Message(msg) => assert_eq!(msg.data(), data.as_bytes()),
+            Event::Retry(_) => panic!("unexpected retry event"),
+        }
+    }
+    assert!(reader.next().await.is_none());
+    drop((sender, clone));
+    Ok(())
+}
+
+#[async_std::test]
+async fn close_with_retry() -> http_types::Result<()> {
+    let (sender, mut encoder) = EncoderBuilder::new().capacity(4).build();
+    sender.send(None, "chashu", None).await?;
+    let retry = Duration::from_secs(5);
+    sender
+        .close_with_retry(retry, "reconnect elsewhere")
+        .await?;
+    assert!(sender.send(None, "nori", None).await.is_err());
+
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(
+        output,
+        "data:chashu\n\n:reconnect elsewhere\nretry:5000\n\n"
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn close_with_retry_waits_for_room() -> http_types::Result<()> {
+    let (sender, encoder) = EncoderBuilder::new().build();
+    sender.send(None, "chashu", None).await?;
+    task::spawn(async move {
+        let retry = Duration::from_millis(1500);
+        sender.close_with_retry(retry, "bye").await
+    });
+
+    let events: Vec<Event> = decode(BufReader::new(encoder))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    assert!(events[0].is_message());
+    assert_eq!(events[1], Event::Retry(Duration::from_millis(1500)));
+    Ok(())
+}
+
+#[async_std::test]
+async fn cancelled_close_with_retry_still_closes() -> http_types::Result<()> {
+    let (sender, mut encoder) = EncoderBuilder::new().build();
+    sender.send(None, "chashu", None).await?;
+    let retry = Duration::from_secs(5);
+    assert!(future::poll_once(sender.close_with_retry(retry, "bye"))
+        .await
+        .is_none());
+    assert!(sender.is_closed());
+
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(output, "data:chashu\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn close_wakes_closed() -> http_types::Result<()> {
+    let (sender, _encoder) = EncoderBuilder::new().build();
+    let waiter = {
+        let sender = sender.clone();
+        task::spawn(async move { sender.closed().await })
+    };
+    sender.close();
+    waiter.await;
+    Ok(())
+}
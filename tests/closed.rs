@@ -0,0 +1,69 @@
+use async_sse::{encode, Backpressure, EncoderBuilder};
+use async_std::task;
+use futures_lite::future;
+use std::time::Duration;
+
+#[async_std::test]
+async fn closed_when_encoder_dropped() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    assert!(!sender.is_closed());
+    assert!(future::poll_once(sender.closed()).await.is_none());
+
+    drop(encoder);
+    assert!(sender.is_closed());
+    sender.closed().await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn closed_wakes_every_clone() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let waiters: Vec<_> = (0..4)
+        .map(|_| {
+            let sender = sender.clone();
+            task::spawn(async move { sender.closed().await })
+        })
+        .collect();
+
+    task::sleep(Duration::from_millis(10)).await;
+    drop(encoder);
+    for waiter in waiters {
+        waiter.await;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn closed_while_send_is_pending() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    sender.send(None, "chashu", None).await?;
+    let waiter = {
+        let sender = sender.clone();
+        task::spawn(async move { sender.closed().await })
+    };
+
+    task::spawn(async move {
+        task::sleep(Duration::from_millis(10)).await;
+        drop(encoder);
+    });
+    assert!(sender.send(None, "nori", None).await.is_err());
+    waiter.await;
+    Ok(())
+}
+
+#[async_std::test]
+async fn closed_on_disconnect_policy() -> http_types::Result<()> {
+    let (sender, _encoder) = EncoderBuilder::new()
+        .backpressure(Backpressure::Disconnect)
+        .build();
+    let waiter = {
+        let sender = sender.clone();
+        task::spawn(async move { sender.closed().await })
+    };
+
+    sender.send(None, "chashu", None).await?;
+    assert!(sender.send(None, "nori", None).await.is_err());
+    assert!(sender.is_closed());
+    waiter.await;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use async_sse::{EncoderBuilder, Sender};
+use async_std::prelude::*;
+use std::io::IoSliceMut;
+
+/// Queue three small events.
+async fn queue_events(sender: &Sender) -> http_types::Result<()> {
+    for data in &["1", "2", "3"] {
+        sender.send(None, data, None).await?;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn single_read_drains_queue() -> http_types::Result<()> {
+    let (sender, mut encoder) = EncoderBuilder::new().capacity(8).build();
+    queue_events(&sender).await?;
+
+    let mut buf = [0; 64];
+    let n = encoder.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"data:1\n\ndata:2\n\ndata:3\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn read_stops_at_capacity() -> http_types::Result<()> {
+    let (sender, mut encoder) = EncoderBuilder::new().capacity(8).build();
+    queue_events(&sender).await?;
+    drop(sender);
+
+    let mut buf = [0; 10];
+    let n = encoder.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"data:1\n\nda");
+    let mut rest = String::new();
+    encoder.read_to_string(&mut rest).await?;
+    assert_eq!(rest, "ta:2\n\ndata:3\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn vectored_read() -> http_types::Result<()> {
+    let (sender, mut encoder) = EncoderBuilder::new().capacity(8).build();
+    queue_events(&sender).await?;
+
+    let (mut first, mut second) = ([0; 10], [0; 64]);
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+    let n = encoder.read_vectored(&mut bufs).await?;
+    assert_eq!(n, 24);
+    assert_eq!(&first, b"data:1\n\nda");
+    assert_eq!(&second[..14], b"ta:2\n\ndata:3\n\n");
+    Ok(())
+}
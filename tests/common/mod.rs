@@ -0,0 +1,16 @@
+use async_sse::{decode, Encoder, Event};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+
+/// Read every remaining event, then assert the stream ends cleanly.
+pub async fn assert_events(encoder: Encoder, expected: &[&str]) -> http_types::Result<()> {
+    let mut reader = decode(BufReader::new(encoder));
+    for data in expected {
+        match reader.next().await.unwrap()? {
+            Event::Message(msg) => assert_eq!(msg.data(), data.as_bytes()),
+            Event::Retry(_) => panic!("unexpected retry event"),
+        }
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use async_sse::{decode, encode, Event};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+
+const TASKS: usize = 16;
+const EVENTS: usize = 64;
+
+/// Build the data payload a given task sends for a given event.
+fn payload(task: usize, n: usize) -> String {
+    format!("task {}\nevent {}\nend {}-{}", task, n, task, n)
+}
+
+/// Assert a decoded event is exactly one of the frames that was sent.
+fn assert_intact(event: &Event) -> (usize, usize) {
+    let msg = match event {
+        Event::Message(msg) => msg,
+        Event::Retry(_) => panic!("unexpected retry event"),
+    };
+    let name = msg.name();
+    let id = msg.id().as_ref().expect("every frame carries an id");
+    let task: usize = name.trim_start_matches("task-").parse().unwrap();
+    let n: usize = id.split('-').nth(1).unwrap().parse().unwrap();
+    assert_eq!(id, &format!("{}-{}", task, n));
+    assert_eq!(
+        String::from_utf8(msg.data().to_owned()).unwrap(),
+        payload(task, n)
+    );
+    (task, n)
+}
+
+#[async_std::test]
+async fn cloned_senders_do_not_interleave() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    for task in 0..TASKS {
+        let sender = sender.clone();
+        task::spawn(async move {
+            for n in 0..EVENTS {
+                let name = format!("task-{}", task);
+                let id = format!("{}-{}", task, n);
+                sender
+                    .send(name.as_str(), &payload(task, n), Some(&id))
+                    .await
+                    .unwrap();
+                task::yield_now().await;
+            }
+        });
+    }
+    drop(sender);
+
+    let mut reader = decode(BufReader::new(encoder));
+    let mut seen = [0; TASKS];
+    while let Some(event) = reader.next().await {
+        let (task, n) = assert_intact(&event?);
+        // Events from a single sender arrive in the order they were sent.
+        assert_eq!(seen[task], n);
+        seen[task] += 1;
+    }
+    assert!(seen.iter().all(|count| *count == EVENTS));
+    Ok(())
+}
+
+#[async_std::test]
+async fn concurrent_retries_and_messages_do_not_interleave() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let retry_sender = sender.clone();
+    task::spawn(async move {
+        for _ in 0..EVENTS {
+            let dur = std::time::Duration::from_secs(5);
+            retry_sender.send_retry(dur, Some("0-0")).await.unwrap();
+            task::yield_now().await;
+        }
+    });
+    task::spawn(async move {
+        for n in 0..EVENTS {
+            let id = format!("0-{}", n);
+            sender
+                .send("task-0", &payload(0, n), Some(&id))
+                .await
+                .unwrap();
+            task::yield_now().await;
+        }
+    });
+
+    let mut reader = decode(BufReader::new(encoder));
+    let (mut retries, mut messages) = (0, 0);
+    while let Some(event) = reader.next().await {
+        match event? {
+            Event::Retry(_) => retries += 1,
+            event => {
+                assert_intact(&event);
+                messages += 1;
+            }
+        }
+    }
+    assert_eq!(retries, EVENTS);
+    assert_eq!(messages, EVENTS);
+    Ok(())
+}
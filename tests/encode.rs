@@ -1,4 +1,4 @@
-use async_sse::{decode, encode, Event};
+use async_sse::{decode, encode, DecoderBuilder, EncoderBuilder, Event, Message, RetryUnit};
 use async_std::io::BufReader;
 use async_std::prelude::*;
 use async_std::task;
@@ -17,10 +17,9 @@ fn assert_message(event: &Event, name: &str, data: &str, id: Option<&'static str
     }
 }
 
-/// Assert a Message.
-fn assert_retry(event: &Event, dur: u64) {
+/// Assert a Retry.
+fn assert_retry(event: &Event, expected: Duration) {
     assert!(event.is_retry());
-    let expected = Duration::from_secs_f64(dur as f64);
     if let Event::Retry(dur) = event {
         assert_eq!(dur, &expected);
     }
@@ -91,7 +90,53 @@ async fn encode_retry() -> http_types::Result<()> {
 
     let mut reader = decode(BufReader::new(encoder));
     let event = reader.next().await.unwrap()?;
-    assert_retry(&event, 12);
+    assert_retry(&event, Duration::from_secs(12));
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_retry_millis() -> http_types::Result<()> {
+    let (sender, mut encoder) = encode();
+    task::spawn(async move {
+        let dur = Duration::from_millis(1500);
+        sender.send_retry(dur, None).await.unwrap();
+    });
+
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(output, "retry:1500\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_retry_sub_second() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    task::spawn(async move {
+        sender.send_retry(Duration::from_millis(1500), None).await?;
+        sender.send_retry(Duration::from_millis(1), None).await
+    });
+
+    let mut reader = decode(BufReader::new(encoder));
+    let event = reader.next().await.unwrap()?;
+    assert_retry(&event, Duration::from_millis(1500));
+    let event = reader.next().await.unwrap()?;
+    assert_retry(&event, Duration::from_millis(1));
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_retry_legacy_seconds() -> http_types::Result<()> {
+    let (sender, encoder) = EncoderBuilder::new().retry_unit(RetryUnit::Seconds).build();
+    task::spawn(async move {
+        let dur = Duration::from_millis(12500);
+        sender.send_retry(dur, None).await.unwrap();
+    });
+
+    let mut reader = DecoderBuilder::new()
+        .retry_unit(RetryUnit::Seconds)
+        .build(BufReader::new(encoder));
+    let event = reader.next().await.unwrap()?;
+    assert_retry(&event, Duration::from_secs(12));
     Ok(())
 }
 
@@ -126,3 +171,140 @@ async fn dropping_encoder() -> http_types::Result<()> {
     );
     Ok(())
 }
+
+#[async_std::test]
+async fn encode_comment() -> http_types::Result<()> {
+    let (sender, mut encoder) = encode();
+    task::spawn(async move { sender.send_comment("chashu\r\nnori\rmochi\n").await });
+
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(output, ":chashu\n:nori\n:mochi\n:\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_comment_is_ignored_by_decoder() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    task::spawn(async move {
+        sender.send_comment("").await?;
+        sender.send("cat", "chashu", None).await?;
+        sender.send_comment(&" ".repeat(2048)).await?;
+        sender.send_comment("padding\n\ndata:injected\n").await?;
+        sender.send("cat", "nori", None).await
+    });
+
+    let mut reader = decode(BufReader::new(encoder));
+    let event = reader.next().await.unwrap()?;
+    assert_message(&event, "cat", "chashu", None);
+    let event = reader.next().await.unwrap()?;
+    assert_message(&event, "cat", "nori", None);
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+/// Send `data` and return what the decoder hands back.
+async fn round_trip(data: &'static str) -> http_types::Result<String> {
+    let (sender, encoder) = encode();
+    task::spawn(async move { sender.send(None, data, None).await });
+
+    let mut reader = decode(BufReader::new(encoder));
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => Ok(String::from_utf8(msg.into_bytes())?),
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+}
+
+#[async_std::test]
+async fn encode_lossless_data() -> http_types::Result<()> {
+    for data in &["", "\n", "\n\n", "a\n", "\na", "a\n\nb", " a", "  a\n b"] {
+        assert_eq!(&round_trip(data).await?, data);
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_carriage_returns() -> http_types::Result<()> {
+    assert_eq!(round_trip("a\rb").await?, "a\nb");
+    assert_eq!(round_trip("a\r\nb").await?, "a\nb");
+    assert_eq!(round_trip("a\r").await?, "a\n");
+    assert_eq!(round_trip("\r\n\r").await?, "\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_leading_space() -> http_types::Result<()> {
+    let (sender, mut encoder) = encode();
+    task::spawn(async move { sender.send(" cat", " chashu", Some(" 0")).await });
+
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(output, "event:  cat\nid:  0\ndata:  chashu\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_message_value() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    task::spawn(async move {
+        let msg = Message::new("chashu\nnori").with_name("cats").with_id("0");
+        sender.send_message(&msg).await?;
+        sender.send_message(&Message::new("mochi")).await
+    });
+
+    let mut reader = decode(BufReader::new(encoder));
+    let event = reader.next().await.unwrap()?;
+    assert_message(&event, "cats", "chashu\nnori", Some("0"));
+    let event = reader.next().await.unwrap()?;
+    assert_message(&event, "message", "mochi", Some("0"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_message_non_utf8() -> http_types::Result<()> {
+    let (sender, mut encoder) = encode();
+    task::spawn(async move {
+        let msg = Message::new(vec![0xff, b'\r', 0xfe, b'\n']);
+        sender.send_message(&msg).await
+    });
+
+    let mut output = vec![];
+    encoder.read_to_end(&mut output).await?;
+    assert_eq!(output, b"data:\xff\ndata:\xfe\ndata:\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_message_invalid_name() -> http_types::Result<()> {
+    let (sender, _encoder) = encode();
+    let msg = Message::new("chashu").with_name("cat\ndata:evil");
+    let err = sender.send_message(&msg).await.unwrap_err();
+    assert_eq!(err.kind(), async_std::io::ErrorKind::InvalidInput);
+    Ok(())
+}
+
+#[async_std::test]
+async fn relay_decoded_events() -> http_types::Result<()> {
+    let input = "retry:1500\n\nevent:cat\nid:0\ndata:chashu\n\ndata: nori\ndata:\n\n";
+    let upstream: Vec<Event> = decode(async_std::io::Cursor::new(input))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(upstream.len(), 3);
+
+    let (sender, encoder) = encode();
+    let events = upstream.clone();
+    task::spawn(async move {
+        for event in &events {
+            sender.send_event(event).await?;
+        }
+        async_std::io::Result::Ok(())
+    });
+
+    let relayed: Vec<Event> = decode(BufReader::new(encoder))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(relayed, upstream);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use async_sse::{decode, encode_stream, Event, Message};
+use async_std::prelude::*;
+use futures_lite::{future, stream};
+use std::io;
+use std::time::Duration;
+
+#[async_std::test]
+async fn encode_events() -> http_types::Result<()> {
+    let events: Vec<Event> = vec![
+        Message::new("chashu").with_name("cat").with_id("0").into(),
+        Event::Retry(Duration::from_millis(1500)),
+        Message::new("nori\n").with_id("1").into(),
+    ];
+    let decoded: Vec<Event> = decode(encode_stream(stream::iter(events.clone())))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(decoded, events);
+    Ok(())
+}
+
+#[async_std::test]
+async fn encode_messages() -> http_types::Result<()> {
+    let messages = stream::iter(vec![Message::new("chashu"), Message::new("nori")]);
+    let mut output = String::new();
+    encode_stream(messages).read_to_string(&mut output).await?;
+    assert_eq!(output, "data:chashu\n\ndata:nori\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn small_reads() -> http_types::Result<()> {
+    let messages = stream::iter(vec![Message::new("chashu"), Message::new("nori")]);
+    let mut encoder = encode_stream(messages);
+    let mut output = vec![];
+    let mut buf = [0; 3];
+    loop {
+        let n = encoder.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        output.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(output, b"data:chashu\n\ndata:nori\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn pulls_lazily() -> http_types::Result<()> {
+    let (sender, receiver) = async_channel::unbounded::<Message>();
+    let mut encoder = encode_stream(Box::pin(receiver));
+    let mut buf = [0; 64];
+    assert!(future::poll_once(encoder.read(&mut buf)).await.is_none());
+
+    sender.send(Message::new("chashu")).await?;
+    let n = encoder.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b"data:chashu\n\n");
+
+    drop(sender);
+    assert_eq!(encoder.read(&mut buf).await?, 0);
+    Ok(())
+}
+
+#[async_std::test]
+async fn invalid_event_is_an_error() -> http_types::Result<()> {
+    let messages = stream::iter(vec![Message::new("chashu").with_id("0\n")]);
+    let mut output = String::new();
+    let err = encode_stream(messages)
+        .read_to_string(&mut output)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
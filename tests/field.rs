@@ -0,0 +1,101 @@
+use async_sse::{decode, encode, EventId, EventName, InvalidField};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+use std::convert::TryFrom;
+use std::io;
+use std::time::Duration;
+
+/// Assert a send was rejected because of `field`, and extract the error.
+fn assert_invalid(res: io::Result<()>, field: &str) -> InvalidField {
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = *err
+        .into_inner()
+        .unwrap()
+        .downcast::<InvalidField>()
+        .unwrap();
+    assert_eq!(err.field(), field);
+    err
+}
+
+#[async_std::test]
+async fn rejects_line_breaks_in_name() -> http_types::Result<()> {
+    let (sender, _encoder) = encode();
+    let res = sender.send("cat\ndata:evil", "chashu", None).await;
+    assert_eq!(assert_invalid(res, "event").byte(), b'\n');
+    let res = sender.send("cat\r", "chashu", None).await;
+    assert_eq!(assert_invalid(res, "event").byte(), b'\r');
+    Ok(())
+}
+
+#[async_std::test]
+async fn rejects_invalid_ids() -> http_types::Result<()> {
+    let (sender, _encoder) = encode();
+    let res = sender.send("cat", "chashu", Some("1\nretry:0")).await;
+    assert_eq!(assert_invalid(res, "id").byte(), b'\n');
+    let res = sender.send("cat", "chashu", Some("1\0")).await;
+    assert_eq!(assert_invalid(res, "id").byte(), b'\0');
+    let res = sender.send_retry(Duration::from_secs(1), Some("1\r")).await;
+    assert_eq!(assert_invalid(res, "id").byte(), b'\r');
+    Ok(())
+}
+
+#[async_std::test]
+async fn rejected_sends_are_not_written() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    task::spawn(async move {
+        assert!(sender.send("cat\n", "chashu", None).await.is_err());
+        assert!(sender.send("cat", "chashu", Some("\0")).await.is_err());
+        sender.send("cat", "nori", None).await
+    });
+
+    let mut output = String::new();
+    BufReader::new(encoder).read_to_string(&mut output).await?;
+    assert_eq!(output, "event:cat\ndata:nori\n\n");
+    Ok(())
+}
+
+#[test]
+fn escape_keeps_values_distinct() {
+    assert_eq!(EventName::escape("a\\n").as_str(), "a\\\\n");
+    assert_ne!(EventName::escape("a\\n"), EventName::escape("a\n"));
+    assert_ne!(EventId::escape("1\\0"), EventId::escape("1\0"));
+    assert_eq!(EventId::escape("1\\r\r").as_str(), "1\\\\r\\r");
+}
+
+#[async_std::test]
+async fn escaped_fields_round_trip() -> http_types::Result<()> {
+    let name = EventName::escape("cat\ndata:evil");
+    let id = EventId::escape("1\r\n\0");
+    assert_eq!(name.as_str(), "cat\\ndata:evil");
+    assert_eq!(id.as_str(), "1\\r\\n\\0");
+
+    let (sender, encoder) = encode();
+    task::spawn(async move { sender.send(name.as_str(), "chashu", Some(&id)).await });
+
+    let mut reader = decode(BufReader::new(encoder));
+    match reader.next().await.unwrap()? {
+        async_sse::Event::Message(msg) => {
+            assert_eq!(msg.name(), "cat\\ndata:evil");
+            assert_eq!(msg.id().as_deref(), Some("1\\r\\n\\0"));
+            assert_eq!(msg.data(), b"chashu");
+        }
+        async_sse::Event::Retry(_) => panic!("unexpected retry event"),
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[test]
+fn validated_constructors() {
+    assert!(EventName::new("cat").is_ok());
+    assert!(EventName::new("cat\0").is_ok());
+    assert_eq!(EventName::new("a\nb").unwrap_err().field(), "event");
+    assert!(EventId::try_from("42").is_ok());
+    assert_eq!(EventId::try_from("4\0").unwrap_err().byte(), b'\0');
+    assert_eq!(
+        EventId::new("\n").unwrap_err().to_string(),
+        "sse id field contains an invalid character '\\n'"
+    );
+}
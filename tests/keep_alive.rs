@@ -0,0 +1,121 @@
+use async_sse::{decode, Clock, Encoder, EncoderBuilder, Event, Sleep};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use futures_lite::future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const INTERVAL: Duration = Duration::from_secs(30);
+
+/// A clock whose timers only fire when the test says so.
+#[derive(Debug, Clone, Default)]
+struct ManualClock {
+    timers: Arc<Mutex<Vec<async_channel::Sender<()>>>>,
+    requested: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl ManualClock {
+    /// Fire every pending timer.
+    fn advance(&self) {
+        self.timers.lock().unwrap().clear();
+    }
+}
+
+impl Clock for ManualClock {
+    fn sleep(&self, dur: Duration) -> Sleep {
+        let (sender, receiver) = async_channel::bounded::<()>(1);
+        self.timers.lock().unwrap().push(sender);
+        self.requested.lock().unwrap().push(dur);
+        Box::pin(async move {
+            let _ = receiver.recv().await;
+        })
+    }
+}
+
+/// Read whatever the encoder has ready, or `None` if it would block.
+async fn try_read(encoder: &mut Encoder) -> Option<String> {
+    let mut buf = [0; 64];
+    let n = future::poll_once(encoder.read(&mut buf)).await?.unwrap();
+    Some(String::from_utf8(buf[..n].to_vec()).unwrap())
+}
+
+#[async_std::test]
+async fn sends_comment_when_idle() -> http_types::Result<()> {
+    let clock = ManualClock::default();
+    let (_sender, mut encoder) = EncoderBuilder::new()
+        .keep_alive(INTERVAL)
+        .clock(clock.clone())
+        .build();
+
+    assert_eq!(try_read(&mut encoder).await, None);
+    clock.advance();
+    assert_eq!(try_read(&mut encoder).await.as_deref(), Some(":\n"));
+    assert_eq!(try_read(&mut encoder).await, None);
+    clock.advance();
+    assert_eq!(try_read(&mut encoder).await.as_deref(), Some(":\n"));
+    assert!(clock
+        .requested
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|d| *d == INTERVAL));
+    Ok(())
+}
+
+#[async_std::test]
+async fn events_reset_the_timer() -> http_types::Result<()> {
+    let clock = ManualClock::default();
+    let (sender, mut encoder) = EncoderBuilder::new()
+        .keep_alive(INTERVAL)
+        .clock(clock.clone())
+        .build();
+
+    // The interval elapses, but an event is ready to go out instead.
+    clock.advance();
+    sender.send("cat", "chashu", None).await?;
+    let frame = try_read(&mut encoder).await.unwrap();
+    assert_eq!(frame, "event:cat\ndata:chashu\n\n");
+    assert_eq!(try_read(&mut encoder).await, None);
+    Ok(())
+}
+
+#[async_std::test]
+async fn decoder_ignores_keep_alive() -> http_types::Result<()> {
+    let clock = ManualClock::default();
+    let (sender, encoder) = EncoderBuilder::new()
+        .keep_alive(INTERVAL)
+        .clock(clock.clone())
+        .build();
+
+    let mut reader = decode(BufReader::new(encoder));
+    assert!(future::poll_once(reader.next()).await.is_none());
+    clock.advance();
+    assert!(future::poll_once(reader.next()).await.is_none());
+    sender.send("cat", "chashu", None).await?;
+    drop(sender);
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => assert_eq!(msg.data(), b"chashu"),
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[cfg(feature = "async-io")]
+#[async_std::test]
+async fn system_clock_keep_alive() -> http_types::Result<()> {
+    let (_sender, mut encoder) = EncoderBuilder::new()
+        .keep_alive(Duration::from_millis(10))
+        .build();
+
+    let mut buf = [0; 8];
+    let n = encoder.read(&mut buf).await?;
+    assert_eq!(&buf[..n], b":\n");
+    Ok(())
+}
+
+#[test]
+fn encoder_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Encoder>();
+}
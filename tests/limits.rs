@@ -0,0 +1,195 @@
+use async_sse::{DecoderBuilder, Event, Limit, LimitExceeded};
+use async_std::io::{BufReader, Cursor};
+use async_std::prelude::*;
+use std::io;
+
+const MIB: usize = 1024 * 1024;
+
+/// Get the limit which caused a decoder error.
+fn limit(err: &http_types::Error) -> Limit {
+    let err = err.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = err.get_ref().unwrap();
+    err.downcast_ref::<LimitExceeded>().unwrap().limit()
+}
+
+/// Get the data of a message.
+fn data(event: Event) -> String {
+    match event {
+        Event::Message(msg) => String::from_utf8(msg.into_bytes()).unwrap(),
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+}
+
+/// A frame with a single `data` line of `len` bytes.
+fn big_frame(len: usize) -> Vec<u8> {
+    let mut frame = b"data:".to_vec();
+    frame.resize(frame.len() + len, b'a');
+    frame.extend_from_slice(b"\n\n");
+    frame
+}
+
+#[async_std::test]
+async fn endless_line() -> http_types::Result<()> {
+    let reader = BufReader::new(futures_lite::io::repeat(b'a'));
+    let mut reader = DecoderBuilder::new().max_line_len(MIB).build(reader);
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::LineLength);
+    Ok(())
+}
+
+#[async_std::test]
+async fn line_too_long() -> http_types::Result<()> {
+    let mut input = big_frame(4 * MIB);
+    input.extend_from_slice(b"data:ok\n\n");
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(MIB)
+        .build(Cursor::new(input));
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::LineLength);
+    assert_eq!(data(reader.next().await.unwrap()?), "ok");
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn line_too_long_skips_event() -> http_types::Result<()> {
+    let mut input = b"event:big\n".to_vec();
+    input.extend_from_slice(&big_frame(2 * MIB)[..2 * MIB + 6]);
+    input.extend_from_slice(b"data:tail\n\ndata:ok\n\n");
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(MIB)
+        .build(Cursor::new(input));
+    assert!(reader.next().await.unwrap().is_err());
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => {
+            assert_eq!(msg.name(), "message");
+            assert_eq!(msg.data(), b"ok");
+        }
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn line_within_limit() -> http_types::Result<()> {
+    let input = big_frame(4 * MIB);
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(5 * MIB)
+        .max_event_size(5 * MIB)
+        .build(Cursor::new(input));
+    assert_eq!(data(reader.next().await.unwrap()?).len(), 4 * MIB);
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn event_too_large() -> http_types::Result<()> {
+    let mut input = b"data:aaaa\n".repeat(MIB);
+    input.extend_from_slice(b"\ndata:ok\n\n");
+    let mut reader = DecoderBuilder::new()
+        .max_event_size(MIB)
+        .build(Cursor::new(input));
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::EventSize);
+    assert_eq!(data(reader.next().await.unwrap()?), "ok");
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn event_size_boundary() -> http_types::Result<()> {
+    // "chashu" and "abc\nde" are exactly 6 bytes, "chashu!" is 7.
+    let input = "data:chashu\n\ndata:abc\ndata:de\n\ndata:chashu!\n\ndata:ab\ndata:cdef\n\n";
+    let mut reader = DecoderBuilder::new()
+        .max_event_size(6)
+        .build(Cursor::new(input));
+    assert_eq!(data(reader.next().await.unwrap()?), "chashu");
+    assert_eq!(data(reader.next().await.unwrap()?), "abc\nde");
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::EventSize);
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::EventSize);
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn id_too_long() -> http_types::Result<()> {
+    let input = "id:chashu\ndata:x\n\ndata:y\n\n";
+    let mut reader = DecoderBuilder::new()
+        .max_id_len(4)
+        .build(Cursor::new(input));
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::IdLength);
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => {
+            assert_eq!(msg.data(), b"y");
+            assert_eq!(msg.id(), &None);
+        }
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn skipped_event_ignores_fields() -> http_types::Result<()> {
+    let input = "data:chashu!\nid:1\nretry:10\nid:chashu\ndata:x\n\ndata:ok\n\n";
+    let mut reader = DecoderBuilder::new()
+        .max_event_size(6)
+        .max_id_len(4)
+        .build(Cursor::new(input));
+    let err = reader.next().await.unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::EventSize);
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => {
+            assert_eq!(msg.data(), b"ok");
+            assert_eq!(msg.id(), &None);
+        }
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+    assert!(reader.next().await.is_none());
+    assert_eq!(reader.last_event_id(), None);
+    assert_eq!(reader.reconnection_time(), None);
+    Ok(())
+}
+
+#[async_std::test]
+async fn skip_oversized() -> http_types::Result<()> {
+    let mut input = big_frame(4 * MIB);
+    input.extend_from_slice(&b"data:aaaa\n".repeat(MIB));
+    input.extend_from_slice(b"\nid:chashu\ndata:x\n\ndata:ok\n\n");
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(MIB)
+        .max_event_size(MIB)
+        .max_id_len(4)
+        .skip_oversized(true)
+        .build(Cursor::new(input));
+    assert_eq!(data(reader.next().await.unwrap()?), "ok");
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[test]
+fn blocking_endless_line() {
+    let reader = io::BufReader::new(io::repeat(b'a'));
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(MIB)
+        .build_blocking(reader);
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(limit(&err), Limit::LineLength);
+}
+
+#[test]
+fn limit_exceeded_display() {
+    let mut reader = DecoderBuilder::new()
+        .max_line_len(4)
+        .build_blocking(&b"data:chashu\n\n"[..]);
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<io::Error>().unwrap().to_string(),
+        "sse line exceeds the limit of 4 bytes"
+    );
+}
@@ -0,0 +1,39 @@
+use async_sse::{Event, Message};
+
+#[test]
+fn new_message_defaults() {
+    let msg = Message::new("chashu");
+    assert_eq!(msg.name(), "message");
+    assert_eq!(msg.id(), &None);
+    assert_eq!(msg.data(), b"chashu");
+}
+
+#[test]
+fn builder() {
+    let msg = Message::new(vec![0xff, 0x00]).with_name("cat").with_id("0");
+    assert_eq!(msg.name(), "cat");
+    assert_eq!(msg.id().as_deref(), Some("0"));
+    assert_eq!(msg.clone().into_bytes(), vec![0xff, 0x00]);
+}
+
+#[test]
+fn setters() {
+    let mut msg = Message::new("chashu").with_id("0");
+    msg.set_name("cat");
+    msg.set_data("nori");
+    msg.clear_id();
+    assert_eq!(msg, Message::new("nori").with_name("cat"));
+    msg.set_id("1");
+    assert_eq!(msg.id().as_deref(), Some("1"));
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Message::from("chashu"), Message::new(b"chashu".to_vec()));
+    assert_eq!(Message::from("chashu".to_string()), Message::new("chashu"));
+    assert_eq!(Message::from(b"chashu".to_vec()), Message::new("chashu"));
+
+    let event = Event::from(Message::new("chashu"));
+    assert!(event.is_message());
+    assert_eq!(event.clone(), event);
+}
@@ -0,0 +1,71 @@
+use async_sse::{decode, encode, Event};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use async_std::task;
+use proptest::prelude::*;
+
+/// Send a single message, and decode everything the encoder wrote.
+fn round_trip(name: Option<String>, data: String, id: Option<String>) -> Vec<Event> {
+    task::block_on(async move {
+        let (sender, encoder) = encode();
+        task::spawn(async move {
+            sender
+                .send(name.as_deref(), &data, id.as_deref())
+                .await
+                .unwrap()
+        });
+
+        let reader = decode(BufReader::new(encoder));
+        reader.map(|event| event.unwrap()).collect().await
+    })
+}
+
+/// The decoder reports CR and CRLF line endings as LF.
+fn normalize_newlines(data: &str) -> String {
+    data.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+proptest! {
+    #[test]
+    fn data_round_trips(data in any::<String>()) {
+        let events = round_trip(None, data.clone(), None);
+        prop_assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Message(msg) => {
+                prop_assert_eq!(msg.name(), "message");
+                prop_assert_eq!(msg.data(), normalize_newlines(&data).into_bytes());
+            }
+            Event::Retry(_) => prop_assert!(false, "unexpected retry event"),
+        }
+    }
+
+    #[test]
+    fn line_endings_round_trip(data in "[a \r\n]*") {
+        let events = round_trip(None, data.clone(), None);
+        match &events[0] {
+            Event::Message(msg) => {
+                prop_assert_eq!(msg.data(), normalize_newlines(&data).into_bytes());
+            }
+            Event::Retry(_) => prop_assert!(false, "unexpected retry event"),
+        }
+    }
+
+    #[test]
+    fn fields_round_trip(
+        name in "[^\r\n]+",
+        data in any::<String>(),
+        id in "[^\r\n\0]*",
+    ) {
+        let events = round_trip(Some(name.clone()), data.clone(), Some(id.clone()));
+        prop_assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Message(msg) => {
+                prop_assert_eq!(msg.name(), &name);
+                // An empty id resets the last event id.
+                prop_assert_eq!(msg.id(), &Some(id).filter(|id| !id.is_empty()));
+                prop_assert_eq!(msg.data(), normalize_newlines(&data).into_bytes());
+            }
+            Event::Retry(_) => prop_assert!(false, "unexpected retry event"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use async_sse::{decode, encode, Event, Message};
+use async_std::io::BufReader;
+use async_std::task;
+use futures_util::{stream, SinkExt, StreamExt};
+use std::io;
+use std::time::Duration;
+
+#[async_std::test]
+async fn forward_stream() -> http_types::Result<()> {
+    let events: Vec<Event> = vec![
+        Message::new("chashu").with_name("cat").into(),
+        Event::Retry(Duration::from_millis(1500)),
+        Message::new("nori").with_id("1").into(),
+    ];
+    let (sender, encoder) = encode();
+    let input = stream::iter(events.clone().into_iter().map(Ok));
+    let forward = task::spawn(input.forward(sender.into_sink()));
+
+    let decoded: Vec<Event> = decode(BufReader::new(encoder))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(decoded, events);
+    forward.await?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn send_messages() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    task::spawn(async move {
+        let mut sink = sender.into_sink();
+        sink.send(Message::new("chashu")).await?;
+        sink.send(Message::new("nori")).await?;
+        SinkExt::<Message>::close(&mut sink).await
+    });
+
+    let decoded: Vec<Event> = decode(BufReader::new(encoder))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    let expected: Vec<Event> = vec![Message::new("chashu").into(), Message::new("nori").into()];
+    assert_eq!(decoded, expected);
+    Ok(())
+}
+
+#[async_std::test]
+async fn ready_waits_for_room() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let mut sink = sender.into_sink();
+    sink.feed(Message::new("chashu")).await?;
+    sink.feed(Message::new("nori")).await?;
+    // The queue holds a single event, so the second one is still pending.
+    assert!(
+        futures_lite::future::poll_once(SinkExt::<Message>::flush(&mut sink))
+            .await
+            .is_none()
+    );
+
+    let mut reader = decode(BufReader::new(encoder));
+    assert!(reader.next().await.unwrap()?.is_message());
+    SinkExt::<Message>::flush(&mut sink).await?;
+    drop(sink);
+    assert!(reader.next().await.unwrap()?.is_message());
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn errors() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let mut sink = sender.into_sink();
+
+    let err = sink
+        .send(Message::new("chashu").with_name("a\nb"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    drop(encoder);
+    let err = sink.send(Message::new("chashu")).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+    Ok(())
+}
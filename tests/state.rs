@@ -0,0 +1,156 @@
+use async_sse::{
+    decode, decode_blocking, decode_with_state, DecoderBuilder, DecoderState, Event, RetryUnit,
+};
+use async_std::io::Cursor;
+use async_std::prelude::*;
+use std::time::Duration;
+
+#[async_std::test]
+async fn last_event_id() -> http_types::Result<()> {
+    let input = "id:1\ndata:a\n\ndata:b\n\nid:2\n\nid:3\ndata:c\n";
+    let mut reader = decode(Cursor::new(input));
+    assert_eq!(reader.last_event_id(), None);
+    reader.next().await.unwrap()?;
+    assert_eq!(reader.last_event_id(), Some("1"));
+    reader.next().await.unwrap()?;
+    assert_eq!(reader.last_event_id(), Some("1"));
+
+    // A frame without data still updates the id. The last frame never ends,
+    // so its id is never dispatched.
+    assert!(reader.next().await.is_none());
+    assert_eq!(reader.last_event_id(), Some("2"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn reconnection_time() -> http_types::Result<()> {
+    let input = "data:a\n\nretry:1500\ndata:b\n\nretry:x\n\n";
+    let mut reader = decode(Cursor::new(input));
+    assert_eq!(reader.reconnection_time(), None);
+    reader.next().await.unwrap()?;
+    assert_eq!(reader.reconnection_time(), None);
+    reader.next().await.unwrap()?;
+    assert_eq!(
+        reader.reconnection_time(),
+        Some(Duration::from_millis(1500))
+    );
+    reader.next().await.unwrap()?;
+    assert!(reader.next().await.is_none());
+    assert_eq!(
+        reader.reconnection_time(),
+        Some(Duration::from_millis(1500))
+    );
+
+    let mut reader = DecoderBuilder::new()
+        .retry_unit(RetryUnit::Seconds)
+        .build(Cursor::new("retry:3\n\n"));
+    reader.next().await.unwrap()?;
+    assert_eq!(reader.reconnection_time(), Some(Duration::from_secs(3)));
+    Ok(())
+}
+
+#[async_std::test]
+async fn state_survives_errors() -> http_types::Result<()> {
+    let input = &b"retry:2000\nid:1\ndata:a\n\ndata:\xFF\n\n"[..];
+    let mut reader = DecoderBuilder::new()
+        .strict_utf8(true)
+        .build(Cursor::new(input));
+    reader.next().await.unwrap()?;
+    reader.next().await.unwrap()?;
+    assert!(reader.next().await.unwrap().is_err());
+    assert_eq!(reader.last_event_id(), Some("1"));
+    assert_eq!(
+        reader.reconnection_time(),
+        Some(Duration::from_millis(2000))
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn recover_reader() -> http_types::Result<()> {
+    let input = "data:a\n\n";
+    let mut reader = decode(Cursor::new(input));
+    assert_eq!(reader.get_ref().position(), 0);
+    reader.next().await.unwrap()?;
+    assert!(reader.next().await.is_none());
+    reader.get_mut().set_position(0);
+    assert!(reader.next().await.unwrap()?.is_message());
+    let cursor = reader.into_inner();
+    assert_eq!(cursor.position(), input.len() as u64);
+    Ok(())
+}
+
+#[test]
+fn blocking_state() -> http_types::Result<()> {
+    let input = "retry:10\nid:1\ndata:a\n\n";
+    let mut reader = decode_blocking(std::io::Cursor::new(input));
+    reader.next().unwrap()?;
+    reader.next().unwrap()?;
+    assert!(reader.next().is_none());
+    assert_eq!(reader.last_event_id(), Some("1"));
+    assert_eq!(reader.reconnection_time(), Some(Duration::from_millis(10)));
+    assert_eq!(reader.get_ref().position(), input.len() as u64);
+    assert_eq!(reader.into_inner().into_inner(), input);
+    Ok(())
+}
+
+/// Get the id of a message.
+fn id(event: Event) -> Option<String> {
+    match event {
+        Event::Message(msg) => msg.id().clone(),
+        Event::Retry(_) => panic!("unexpected retry event"),
+    }
+}
+
+#[async_std::test]
+async fn seeded_last_event_id() -> http_types::Result<()> {
+    let state = DecoderState::new().with_last_event_id("42");
+    let input = "data:a\n\nid:43\ndata:b\n\n";
+    let mut reader = decode_with_state(Cursor::new(input), state);
+    assert_eq!(reader.last_event_id(), Some("42"));
+    assert_eq!(id(reader.next().await.unwrap()?).as_deref(), Some("42"));
+    assert_eq!(id(reader.next().await.unwrap()?).as_deref(), Some("43"));
+    assert_eq!(reader.last_event_id(), Some("43"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn empty_seeded_last_event_id() -> http_types::Result<()> {
+    let state = DecoderState::new().with_last_event_id("");
+    assert_eq!(state.last_event_id(), None);
+    assert_eq!(state, DecoderState::new());
+    let mut reader = decode_with_state(Cursor::new("data:a\n\n"), state);
+    assert_eq!(id(reader.next().await.unwrap()?), None);
+    assert_eq!(reader.last_event_id(), None);
+    Ok(())
+}
+
+#[async_std::test]
+async fn state_carries_over_reconnects() -> http_types::Result<()> {
+    let mut first = decode(Cursor::new("retry:500\nid:1\ndata:a\n\nid:2\ndata:b"));
+    while let Some(event) = first.next().await {
+        event?;
+    }
+    let state = first.into_state();
+    assert_eq!(state.last_event_id(), Some("1"));
+    assert_eq!(state.reconnection_time(), Some(Duration::from_millis(500)));
+
+    let mut second = DecoderBuilder::new()
+        .state(state.clone())
+        .build(Cursor::new("data:c\n\n"));
+    assert_eq!(second.state(), state);
+    assert_eq!(id(second.next().await.unwrap()?).as_deref(), Some("1"));
+    assert_eq!(second.reconnection_time(), Some(Duration::from_millis(500)));
+    Ok(())
+}
+
+#[test]
+fn blocking_state_carries_over() -> http_types::Result<()> {
+    let mut first = decode_blocking(&b"id:7\ndata:a\n\n"[..]);
+    first.next().unwrap()?;
+    let mut second = DecoderBuilder::new()
+        .state(first.into_state())
+        .build_blocking(&b"data:b\n\n"[..]);
+    assert_eq!(id(second.next().unwrap()?).as_deref(), Some("7"));
+    Ok(())
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use async_sse::{
+    encode, Backpressure, Encoder, EncoderBuilder, Event, Message, Sender, TrySendError,
+};
+use async_std::prelude::*;
+use std::io;
+use std::time::Duration;
+
+use common::assert_events;
+
+fn event(data: &str) -> Event {
+    Message::new(data).into()
+}
+
+#[async_std::test]
+async fn try_send_full() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    sender.try_send(event("chashu"))?;
+    let err = sender.try_send(event("nori")).unwrap_err();
+    assert!(err.is_full());
+    assert_eq!(err.into_inner(), event("nori"));
+    drop(sender);
+    assert_events(encoder, &["chashu"]).await
+}
+
+/// Create an encoder with room for one event, and fill it.
+fn full_encoder(backpressure: Backpressure) -> (Sender, Encoder) {
+    let (sender, encoder) = EncoderBuilder::new().backpressure(backpressure).build();
+    sender.try_send(event("chashu")).unwrap();
+    (sender, encoder)
+}
+
+#[async_std::test]
+async fn try_send_block_and_fail() -> http_types::Result<()> {
+    for &policy in &[Backpressure::Block, Backpressure::Fail] {
+        let (sender, encoder) = full_encoder(policy);
+        let err = sender.try_send(event("nori")).unwrap_err();
+        assert!(err.is_full());
+        assert_eq!(err.into_inner(), event("nori"));
+        assert_eq!(sender.dropped_events(), 0);
+        drop(sender);
+        assert_events(encoder, &["chashu"]).await?;
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn try_send_drop_oldest() -> http_types::Result<()> {
+    let (sender, encoder) = full_encoder(Backpressure::DropOldest);
+    sender.try_send(event("nori"))?;
+    assert_eq!(sender.dropped_events(), 1);
+    drop(sender);
+    assert_events(encoder, &["nori"]).await
+}
+
+#[async_std::test]
+async fn try_send_drop_newest() -> http_types::Result<()> {
+    let (sender, encoder) = full_encoder(Backpressure::DropNewest);
+    sender.try_send(event("nori"))?;
+    assert_eq!(sender.dropped_events(), 1);
+    drop(sender);
+    assert_events(encoder, &["chashu"]).await
+}
+
+#[async_std::test]
+async fn try_send_disconnect() -> http_types::Result<()> {
+    let (sender, encoder) = full_encoder(Backpressure::Disconnect);
+    let err = sender.try_send(event("nori")).unwrap_err();
+    assert!(err.is_disconnected());
+    assert_eq!(err.into_inner(), event("nori"));
+    assert_eq!(sender.dropped_events(), 1);
+    assert!(sender.is_closed());
+    assert_events(encoder, &["chashu"]).await
+}
+
+#[async_std::test]
+async fn try_send_disconnected() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    drop(encoder);
+    let err = sender.try_send(event("chashu")).unwrap_err();
+    assert!(err.is_disconnected());
+    assert_eq!(err.into_inner(), event("chashu"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn try_send_invalid() -> http_types::Result<()> {
+    let (sender, _encoder) = encode();
+    let msg = Message::new("chashu").with_id("0\n");
+    match sender.try_send(msg.clone().into()).unwrap_err() {
+        TrySendError::Invalid(event, err) => {
+            assert_eq!(event, Event::Message(msg));
+            assert_eq!(err.field(), "id");
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn try_send_retry() -> http_types::Result<()> {
+    let (sender, mut encoder) = encode();
+    sender.try_send(Event::Retry(Duration::from_millis(1500)))?;
+    drop(sender);
+    let mut output = String::new();
+    encoder.read_to_string(&mut output).await?;
+    assert_eq!(output, "retry:1500\n\n");
+    Ok(())
+}
+
+#[cfg(feature = "async-io")]
+#[async_std::test]
+async fn send_timeout_elapses() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let timeout = Duration::from_millis(10);
+    sender.send_timeout(&event("chashu"), timeout).await?;
+    let err = sender
+        .send_timeout(&event("a\nb\nc"), timeout)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    drop(sender);
+    assert_events(encoder, &["chashu"]).await
+}
+
+#[cfg(feature = "async-io")]
+#[async_std::test]
+async fn send_timeout_sends() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    async_std::task::spawn(async move {
+        for data in &["chashu", "nori"] {
+            let timeout = Duration::from_secs(60);
+            sender.send_timeout(&event(data), timeout).await?;
+        }
+        io::Result::Ok(())
+    });
+    assert_events(encoder, &["chashu", "nori"]).await
+}
+
+#[cfg(not(feature = "async-io"))]
+#[async_std::test]
+async fn send_timeout_without_clock() -> http_types::Result<()> {
+    let (sender, encoder) = encode();
+    let err = sender
+        .send_timeout(&event("chashu"), Duration::from_secs(60))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    drop(sender);
+    assert_events(encoder, &[]).await
+}
@@ -0,0 +1,86 @@
+use async_sse::{
+    decode, encode, encode_comment, encode_event, encode_message, encode_retry, Event, Message,
+};
+use async_std::io::Cursor;
+use async_std::prelude::*;
+use async_std::task;
+use std::time::Duration;
+
+#[test]
+fn message_bytes() {
+    let mut buf = Vec::new();
+    let msg = Message::new("chashu\nnori").with_name("cats").with_id("0");
+    encode_message(&msg, &mut buf).unwrap();
+    assert_eq!(buf, b"event:cats\nid:0\ndata:chashu\ndata:nori\n\n");
+}
+
+#[test]
+fn retry_bytes() {
+    let mut buf = Vec::new();
+    encode_retry(Duration::from_millis(1500), &mut buf);
+    assert_eq!(buf, b"retry:1500\n\n");
+}
+
+#[test]
+fn comment_bytes() {
+    let mut buf = Vec::new();
+    encode_comment("chashu\nnori", &mut buf);
+    assert_eq!(buf, b":chashu\n:nori\n");
+}
+
+#[test]
+fn appends_to_buffer() {
+    let mut buf = b":hello\n".to_vec();
+    encode_event(&Event::Retry(Duration::from_secs(1)), &mut buf).unwrap();
+    encode_event(&Message::new("chashu").into(), &mut buf).unwrap();
+    assert_eq!(buf, b":hello\nretry:1000\n\ndata:chashu\n\n");
+}
+
+#[test]
+fn invalid_message_writes_nothing() {
+    let mut buf = Vec::new();
+    let msg = Message::new("chashu").with_id("0\n");
+    let err = encode_message(&msg, &mut buf).unwrap_err();
+    assert_eq!(err.field(), "id");
+    assert!(buf.is_empty());
+}
+
+#[async_std::test]
+async fn matches_sender_output() -> http_types::Result<()> {
+    let msg = Message::new(" chashu\r\n").with_name("cat").with_id("0");
+    let mut expected = Vec::new();
+    encode_message(&msg, &mut expected)?;
+    encode_retry(Duration::from_millis(1500), &mut expected);
+    encode_comment("bye", &mut expected);
+
+    let (sender, mut encoder) = encode();
+    task::spawn(async move {
+        sender.send_message(&msg).await?;
+        sender.send_retry(Duration::from_millis(1500), None).await?;
+        sender.send_comment("bye").await
+    });
+    let mut output = Vec::new();
+    encoder.read_to_end(&mut output).await?;
+    assert_eq!(output, expected);
+    Ok(())
+}
+
+#[async_std::test]
+async fn decodes_encoded_events() -> http_types::Result<()> {
+    let events = vec![
+        Event::Retry(Duration::from_millis(250)),
+        Message::new("chashu").with_name("cat").with_id("0").into(),
+        Message::new("nori\n").with_id("1").into(),
+    ];
+    let mut buf = Vec::new();
+    for event in &events {
+        encode_event(event, &mut buf)?;
+    }
+
+    let decoded: Vec<Event> = decode(Cursor::new(buf))
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(decoded, events);
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use async_sse::{decode, Event, Message, RetryUnit, SseWriter};
+use async_std::io::BufReader;
+use async_std::prelude::*;
+use futures_lite::future;
+use futures_lite::io::AsyncWrite;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// A writer which accepts a few bytes per call, yields in between, and counts
+/// flushes.
+#[derive(Debug, Default)]
+struct SlowWriter {
+    written: Vec<u8>,
+    flushes: usize,
+    ready: bool,
+}
+
+impl AsyncWrite for SlowWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if !std::mem::replace(&mut self.ready, false) {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let n = buf.len().min(4);
+        self.written.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.flushes += 1;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A writer which always fails.
+#[derive(Debug)]
+struct BrokenWriter;
+
+impl AsyncWrite for BrokenWriter {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context<'_>, _: &[u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[async_std::test]
+async fn writes_frames() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(Vec::new());
+    writer.send("cat", "chashu", Some("0")).await?;
+    writer
+        .send_message(&Message::new("nori").with_name("cat"))
+        .await?;
+    writer.send_comment("keep alive").await?;
+    writer.send_retry(Duration::from_secs(12), None).await?;
+    writer
+        .send_event(&Event::from(Message::new("sesame")))
+        .await?;
+
+    let written = String::from_utf8(writer.into_inner())?;
+    assert_eq!(
+        written,
+        "event:cat\nid:0\ndata:chashu\n\n\
+         event:cat\ndata:nori\n\n\
+         :keep alive\n\
+         retry:12000\n\n\
+         data:sesame\n\n"
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn round_trips_through_decoder() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(Vec::new());
+    writer.send("cat", "chashu\nnori", None).await?;
+    writer.send_retry(Duration::from_secs(3), None).await?;
+
+    let buf = writer.into_inner();
+    let mut reader = decode(BufReader::new(&buf[..]));
+    match reader.next().await.unwrap()? {
+        Event::Message(msg) => {
+            assert_eq!(msg.name(), "cat");
+            assert_eq!(msg.data(), b"chashu\nnori");
+        }
+        Event::Retry(_) => panic!("expected a message"),
+    }
+    match reader.next().await.unwrap()? {
+        Event::Retry(dur) => assert_eq!(dur, Duration::from_secs(3)),
+        Event::Message(_) => panic!("expected a retry"),
+    }
+    assert!(reader.next().await.is_none());
+    Ok(())
+}
+
+#[async_std::test]
+async fn retry_unit() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(Vec::new()).retry_unit(RetryUnit::Seconds);
+    writer.send_retry(Duration::from_secs(12), None).await?;
+    assert_eq!(writer.get_ref(), b"retry:12\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn flush_per_event() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(SlowWriter::default());
+    writer.send(None, "a", None).await?;
+    writer.send(None, "b", None).await?;
+    assert_eq!(writer.get_ref().flushes, 2);
+
+    let mut writer = SseWriter::new(SlowWriter::default()).flush_per_event(false);
+    writer.send(None, "a", None).await?;
+    writer.send(None, "b", None).await?;
+    assert_eq!(writer.get_ref().flushes, 0);
+    writer.flush().await?;
+    assert_eq!(writer.get_ref().flushes, 1);
+    assert_eq!(writer.get_ref().written, b"data:a\n\ndata:b\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn surfaces_write_errors() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(BrokenWriter);
+    let err = writer.send(None, "chashu", None).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    Ok(())
+}
+
+#[async_std::test]
+async fn rejects_invalid_fields() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(Vec::new());
+    let err = writer.send("cat\n", "chashu", None).await.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    writer.send("cat", "chashu", None).await?;
+    assert_eq!(writer.get_ref(), b"event:cat\ndata:chashu\n\n");
+    Ok(())
+}
+
+#[async_std::test]
+async fn cancelled_send_finishes_frame_first() -> http_types::Result<()> {
+    let mut writer = SseWriter::new(SlowWriter::default());
+
+    // The first poll yields, and the second writes part of the frame before
+    // yielding again.
+    {
+        let mut send = Box::pin(writer.send("cat", "chashu", None));
+        assert!(future::poll_once(&mut send).await.is_none());
+        assert!(future::poll_once(&mut send).await.is_none());
+    }
+    let partial = writer.get_ref().written.len();
+    assert!(partial > 0 && partial < b"event:cat\ndata:chashu\n\n".len());
+
+    writer.send(None, "nori", None).await?;
+    assert_eq!(
+        writer.get_ref().written,
+        b"event:cat\ndata:chashu\n\ndata:nori\n\n"
+    );
+    Ok(())
+}